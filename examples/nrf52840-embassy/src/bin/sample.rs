@@ -11,9 +11,9 @@ use embassy_nrf::{
     peripherals,
     twim::{self, Frequency},
 };
-use embassy_time::Timer;
+use embassy_time::{Delay, Timer};
 use tas2563::{
-    ll::{registers::Mode, Tas2563Device},
+    hl::{boost::BoostConfig, clock::Clocking, config::Tas2563Config, tdm::TdmConfig},
     prelude::*,
 };
 use {defmt_rtt as _, panic_probe as _};
@@ -66,283 +66,34 @@ async fn main(_spawner: Spawner) {
 
     let mut hl = tas2563::hl::Tas2563::new_i2c(twim, tas2563::ll::i2c::Address::Global);
 
-    {
-        let ll = hl.ll();
-
-        ll.software_reset()
-            .write_async(|w| w.software_reset(true))
-            .await
-            .unwrap();
-
-        let v = ll.software_reset().read_async().await.unwrap();
-        defmt::info!("{:?}", defmt::Debug2Format(&v));
-
-        let v = ll.rev_id().read_async().await.unwrap();
-        defmt::info!("{:?}", defmt::Debug2Format(&v));
-
-        ll.pwr_ctl()
-            .write_async(|w| w.mode(Mode::Mute).vsns_pd(true).isns_pd(true))
-            .await
-            .unwrap();
-
-        ll.pwr_ctl()
-            .write_async(|w| w.mode(Mode::SoftwareShutdown).vsns_pd(true).isns_pd(true))
-            .await
-            .unwrap();
-
-        ll.software_reset()
-            .write_async(|w| w.software_reset(true))
-            .await
-            .unwrap();
-
-        ll.pb_cfg_1()
-            .write_async(|w| w.dis_dc_blocker(false).amp_level(AmpLevel::Unknown))
-            .await
-            .unwrap();
-
-        ll.pwr_ctl()
-            .write_async(|w| w.mode(Mode::SoftwareShutdown).vsns_pd(false).isns_pd(false))
-            .await
-            .unwrap();
-
-        ll.pb_cfg_1()
-            .write_async(|w| w.dis_dc_blocker(false).amp_level(AmpLevel::Amp16DBv0))
-            .await
-            .unwrap();
-
-        ll.misc_cfg_1()
-            .write_async(|w| {
-                w.amp_ss(true)
-                    .irqz_pu(false)
-                    .ote_retry(true)
-                    .oce_retry(true)
-                    .vbat_por_retry(true)
-                    .cp_pg_retry(true)
+    let config = Tas2563Config {
+        boost: BoostConfig::builder()
+            .voltage_v(8.5)
+            .class_h_step_time_us(162)
+            .peak_current_a(0.99)
+            .build()
+            .unwrap(),
+        tdm: TdmConfig::builder()
+            .clocking(Clocking::Manual {
+                sample_rate: SampRate::Rate48Khz,
+                sbclk_fs_ratio: SbclkFsRatio::Ratio256,
             })
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_0()
-            .write_async(|w| {
-                w.frame_start(FrameStart::HighToLow)
-                    .samp_rate(SampRate::Rate48Khz)
-            })
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_1()
-            .write_async(|w| w.rx_offset(0x01))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_2()
-            .write_async(|w| {
-                w.rx_slen(RxSlen::Length32Bits)
-                    .rx_wlen(RxWlen::Length24Bits)
-                    .rx_scfg(RxScfg::StereoDownmix)
-                    .ivmon_len(IvmonLen::Length16Bits)
-            })
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_3()
-            .write_async(|w| w.rx_slot_l(0x0).rx_slot_r(0x1))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_4()
-            .write_async(|w| {
-                w.tx_edge(TxEdge::FallingEdge)
-                    .tx_offset(0x1)
-                    .tx_fill(TxFill::Transmit0)
-            })
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_5()
-            .write_async(|w| w.vsns_slot(0x4).vsns_tx(true))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_6()
-            .write_async(|w| w.isns_slot(0x0).isns_tx(true))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_7()
-            .write_async(|w| w.vbat_slot(0x4).vbat_tx(false))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_8()
-            .write_async(|w| w.temp_slot(0x5).temp_tx(false))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_9()
-            .write_async(|w| w.gain_slot(0x6).gain_tx(false))
-            .await
-            .unwrap();
-
-        ll.tdm_cfg_10()
-            .write_async(|w| w.bst_slot(0x7).bst_tx(false))
-            .await
-            .unwrap();
-
-        ll.lim_cfg_0()
-            .write_async(|w| {
-                w.limb_en(true)
-                    .limb_atk_rt(LimbAtkRt::Step2Samples)
-                    .limb_atk_st(LimbAtkSt::Step0DB5)
-            })
-            .await
-            .unwrap();
-
-        ll.lim_cfg_1()
-            .write_async(|w| {
-                w.limb_hld_tm(LimbHldTm::Time500Ms)
-                    .limb_rls_rt(LimbRlsRt::Step640Ms)
-                    .limb_rls_st(LimbRlsSt::Step0DB5)
-            })
-            .await
-            .unwrap();
-
-        ll.dsp_frequency_bop_cfg_0()
-            .write_async(|w| w.bop_en(true))
-            .await
-            .unwrap();
-
-        ll.bop_cfg_0()
-            .write_async(|w| {
-                w.bop_hld_tm(BopHldTm::Time500Ms)
-                    .bop_atk_st(BopAtkSt::Step1DB0)
-                    .bop_atk_rt(BopAtkRt::Step2Samples)
-            })
-            .await
-            .unwrap();
-
-        ll.int_mask_0().write_async(|w| w).await.unwrap();
-        ll.int_mask_1().write_async(|w| w).await.unwrap();
-        ll.int_mask_2().write_async(|w| w).await.unwrap();
-        ll.int_mask_3().write_async(|w| w).await.unwrap();
-
-        ll.int_clk_cfg()
-            .write_async(|w| w.irqz_pin_cfg(IrqzPinCfg::UnmaskedLatched))
-            .await
-            .unwrap();
-
-        ll.din_pd().write_async(|w| w).await.unwrap();
-
-        ll.misc()
-            .write_async(|w| w.irqz_val(true).irqz_pol(IrqzPol::ActiveLow))
-            .await
-            .unwrap();
-
-        ll.boost_cfg_1()
-            .write_async(|w| {
-                w.bst_dynamic_ilim_en(false)
-                    .bst_pfml(BstPfml::Frequency50Khz)
-                    .bst_en(true)
-                    .bst_mode(BstMode::ClassH)
-            })
-            .await
-            .unwrap();
-
-        ll.boost_cfg_2()
-            .write_async(|w| {
-                w.bst_vreg(BstVreg::Boost8V5)
-                    .bst_pa(BstPa::Phase0Deg)
-                    .bst_sync(false)
-                    .bst_ir(BstIr::Between0UH6And1UH3)
-            })
-            .await
-            .unwrap();
-
-        ll.boost_cfg_3()
-            .write_async(|w| {
-                w.bst_lr(BstLr::LoadReg1V03APerV)
-                    .bst_class_h_step_time(BstClassHStepTime::Step162Us)
-            })
-            .await
-            .unwrap();
-
-        ll.clock_configuration()
-            .write_async(|w| {
-                w.auto_clk(false)
-                    .sbclk_fs_ratio(0b1000)
-                    .sel_madc_div_rev(0x0)
-                    .inv_dac_out_phase(false)
-            })
-            .await
-            .unwrap();
-
-        ll.ramp_frame_select()
-            .write_async(|w| {
-                w.sar_vbat_avg_config(0b11)
-                    .vbat_dsp_lpf_reg(0b01)
-                    .haptic_en(false)
-            })
-            .await
-            .unwrap();
-
-        ll.retry_timer().write_async(|w| w).await.unwrap();
-        ll.hold_sar_update().write_async(|w| w).await.unwrap();
-        ll.idle_channel().write_async(|w| w).await.unwrap();
-
-        ll.tg_cfg_0()
-            .write_async(|w| {
-                w.tg_1_pinen(Tg1Pinen::Disabled)
-                    .tg_1_en(Tg1En::PinTriggered)
-            })
-            .await
-            .unwrap();
-
-        ll.bst_ilim_cfg_0()
-            .write_async(|w| w.bst_ilim(BoostPeakCurrentMaxRun(0)).bst_ssl(0x1))
-            .await
-            .unwrap();
-    }
-
-    tas2563::bulk::CommandIterator::new(include_bytes!(
-        "../../../../util/cfgtransform/example/program_0_Tuning Mode.bulk"
-    ))
-    .write(hl.ll())
-    .await
-    .unwrap();
-
-    tas2563::bulk::CommandIterator::new(include_bytes!(
-        "../../../../util/cfgtransform/example/configuration_0_TuningMode_48KHz_DEV_A_COEFF.bulk"
-    ))
-    .write(hl.ll())
-    .await
-    .unwrap();
-
-    defmt::info!("Configuration loaded");
-
-    hl.ll().reset_assumptions();
-
-    hl.ll()
-        .tdm_cfg_2()
-        .write_async(|w| {
-            w.rx_slen(RxSlen::Length32Bits)
-                .rx_wlen(RxWlen::Length24Bits)
-                .rx_scfg(RxScfg::MonoLeftChannel)
-                .ivmon_len(IvmonLen::Length16Bits)
-        })
-        .await
-        .unwrap();
-
-    hl.ll()
-        .pb_cfg_1()
-        .write_async(|w| w.dis_dc_blocker(false).amp_level(AmpLevel::Amp16DBv0))
-        .await
-        .unwrap();
-
-    hl.ll()
-        .pwr_ctl()
-        .modify_async(|w| w.vsns_pd(false).isns_pd(false).mode(Mode::Active))
-        .await
-        .unwrap();
+            .build()
+            .unwrap(),
+        tuning: &[
+            include_bytes!("../../../../util/cfgtransform/example/program_0_Tuning Mode.bulk_dedup"),
+            include_bytes!(
+                "../../../../util/cfgtransform/example/configuration_0_TuningMode_48KHz_DEV_A_COEFF.bulk_dedup"
+            ),
+        ],
+        mode: Mode::Active,
+        ..Default::default()
+    };
+
+    hl.init(&config, &mut Delay).await.unwrap();
+
+    let v = hl.ll().rev_id().read_async().await.unwrap();
+    defmt::info!("{:?}", defmt::Debug2Format(&v));
 
     defmt::info!("Speaker activated");
 
@@ -1,6 +1,13 @@
-use crate::ll::{Tas2563Device, Tas2563Interface};
+use embedded_hal::delay::DelayNs as BlockingDelayNs;
+use embedded_hal_async::delay::DelayNs;
 
+use crate::ll::{blocking, Tas2563Device, Tas2563Interface, BOOK_REGISTER, PAGE_REGISTER};
+use crate::Error;
+
+/// Followed by the amount of values, the first register and the values, padded to 16 bit words.
 const CFG_META_BURST: u8 = 253;
+/// Followed by the amount of milliseconds to wait.
+const CFG_META_DELAY: u8 = 254;
 
 #[derive(Debug, PartialEq)]
 pub struct RegisterWrite {
@@ -12,11 +19,14 @@ pub struct RegisterWrite {
 pub enum Command<'a> {
     WriteSingle(RegisterWrite),
     WriteBurst(BurstCommand<'a>),
+    Delay(DelayCommand),
 }
 
 #[derive(Debug, PartialEq)]
 pub struct CommandIterator<'a> {
     commands: &'a [u8],
+    /// Page selected by the commands so far, if any.
+    page: Option<u8>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -28,54 +38,159 @@ pub struct BurstCommand<'a> {
     data: &'a [u8],
 }
 
+#[derive(Debug, PartialEq)]
+pub struct DelayCommand {
+    /// Time to wait before issuing the next command.
+    milliseconds: u8,
+}
+
+/// Reasons a bulk command stream can be rejected.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BulkError {
+    /// The stream ends with a single byte, while commands are composed of 16 bit words.
+    OddLength,
+    /// A burst command announces more data than remains in the stream.
+    Truncated,
+    /// A burst command runs past the last register of the page.
+    BurstOverrunsPage,
+    /// A burst command on page 0 switches book part way through by writing the book register (0x7f).
+    BurstCrossesBook,
+}
+
+impl core::fmt::Display for BulkError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BulkError::OddLength => write!(f, "odd number of bytes"),
+            BulkError::Truncated => write!(f, "burst command truncated"),
+            BulkError::BurstOverrunsPage => write!(f, "burst command overruns the page"),
+            BulkError::BurstCrossesBook => write!(f, "burst command crosses the book register"),
+        }
+    }
+}
+
+/// Check a whole bulk command stream without touching the hardware.
+pub fn validate(commands: &[u8]) -> Result<(), BulkError> {
+    CommandIterator::new(commands).validate()
+}
+
 impl<'a> CommandIterator<'a> {
     pub fn new(commands: &'a [u8]) -> Self {
-        Self { commands }
+        Self {
+            commands,
+            page: None,
+        }
+    }
+
+    /// Check the remaining command stream without consuming it.
+    pub fn validate(&self) -> Result<(), BulkError> {
+        CommandIterator {
+            commands: self.commands,
+            page: self.page,
+        }
+        .try_for_each(|c| c.map(|_| ()))
     }
 
+    /// Write all commands to the device, waiting on `delay` where the stream asks for it.
+    ///
+    /// The complete stream is validated before the first write is issued.
+    /// Book and page changes in the stream are tracked by the device, so regular register access
+    /// can continue afterwards.
     pub async fn write<T: Tas2563Interface>(
         self,
         dest: &mut Tas2563Device<T>,
-    ) -> Result<(), T::Error> {
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        self.validate().map_err(Error::Bulk)?;
+
         for c in self {
-            match c {
+            match c.map_err(Error::Bulk)? {
                 Command::WriteSingle(RegisterWrite { register, value }) => {
-                    dest.interface().write_register(register, value).await?;
+                    dest.write_raw(&[register, value]).await?;
                 }
                 Command::WriteBurst(command) => {
-                    dest.interface().write_burst(command.as_burst()).await?;
+                    dest.write_raw(command.as_burst()).await?;
+                }
+                Command::Delay(command) => {
+                    delay.delay_ms(command.as_millis()).await;
                 }
             }
         }
         Ok(())
     }
-}
 
-impl<'a> Iterator for CommandIterator<'a> {
-    type Item = Command<'a>;
+    /// Blocking counterpart of [`CommandIterator::write`].
+    pub fn write_blocking<T: blocking::Tas2563Interface>(
+        self,
+        dest: &mut Tas2563Device<T>,
+        delay: &mut impl BlockingDelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        self.validate().map_err(Error::Bulk)?;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.commands.len() == 0 {
-            return None;
-        }
-        if self.commands.len() == 1 {
-            panic!("Only one byte remaining, expected two");
+        for c in self {
+            match c.map_err(Error::Bulk)? {
+                Command::WriteSingle(RegisterWrite { register, value }) => {
+                    dest.write_raw_blocking(&[register, value])?;
+                }
+                Command::WriteBurst(command) => {
+                    dest.write_raw_blocking(command.as_burst())?;
+                }
+                Command::Delay(command) => {
+                    delay.delay_ms(command.as_millis());
+                }
+            }
         }
+        Ok(())
+    }
+
+    fn parse(&mut self) -> Result<Command<'a>, BulkError> {
+        let (entry, remainder) = self
+            .commands
+            .split_first_chunk::<2>()
+            .ok_or(BulkError::OddLength)?;
 
-        let (entry, remainder) = self.commands.split_at(2);
         let (command, remainder) = if entry[0] == CFG_META_BURST {
             let data_len = entry[1] as usize;
             // Note(2): the address and first value are not part of the burst count
+            if remainder.len() < data_len + 1 {
+                return Err(BulkError::Truncated);
+            }
             let (burst, remainder) = remainder.split_at(data_len + 1);
 
-            let remainder = if data_len % 2 == 0 {
-                &remainder[1..] // Skip zero-padding
+            let remainder = if data_len.is_multiple_of(2) {
+                remainder.get(1..).ok_or(BulkError::Truncated)? // Skip zero-padding
             } else {
                 remainder
             };
 
+            let end = burst[0] as usize + data_len;
+            if end > BOOK_REGISTER as usize + 1 {
+                return Err(BulkError::BurstOverrunsPage);
+            }
+            // The book register only exists on page 0, other pages use it as regular memory.
+            // When the page is not known yet assume the worst.
+            if matches!(self.page, None | Some(0))
+                && burst[0] != BOOK_REGISTER
+                && end > BOOK_REGISTER as usize
+            {
+                return Err(BulkError::BurstCrossesBook);
+            }
+            if burst[0] == PAGE_REGISTER && data_len > 0 {
+                self.page = Some(burst[1]);
+            }
+
             (Command::WriteBurst(BurstCommand { data: burst }), remainder)
+        } else if entry[0] == CFG_META_DELAY {
+            (
+                Command::Delay(DelayCommand {
+                    milliseconds: entry[1],
+                }),
+                remainder,
+            )
         } else {
+            if entry[0] == PAGE_REGISTER {
+                self.page = Some(entry[1]);
+            }
             (
                 Command::WriteSingle(RegisterWrite {
                     register: entry[0],
@@ -86,7 +201,24 @@ impl<'a> Iterator for CommandIterator<'a> {
         };
 
         self.commands = remainder;
-        Some(command)
+        Ok(command)
+    }
+}
+
+impl<'a> Iterator for CommandIterator<'a> {
+    type Item = Result<Command<'a>, BulkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.commands.is_empty() {
+            return None;
+        }
+
+        let result = self.parse();
+        if result.is_err() {
+            // Do not attempt to resynchronize on a corrupted stream.
+            self.commands = &[];
+        }
+        Some(result)
     }
 }
 
@@ -96,11 +228,20 @@ impl<'a> BurstCommand<'a> {
     }
 }
 
+impl DelayCommand {
+    pub fn as_millis(&self) -> u32 {
+        self.milliseconds as u32
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::bulk::BurstCommand;
 
-    use super::{Command, CommandIterator, RegisterWrite, CFG_META_BURST};
+    use super::{
+        validate, BulkError, Command, CommandIterator, DelayCommand, RegisterWrite, CFG_META_BURST,
+        CFG_META_DELAY,
+    };
 
     #[test]
     fn commands_burst_even() {
@@ -119,23 +260,23 @@ mod test {
 
         assert_eq!(
             it.next(),
-            Some(Command::WriteSingle(RegisterWrite {
+            Some(Ok(Command::WriteSingle(RegisterWrite {
                 register: 0x5a,
                 value: 0x0f
-            }))
+            })))
         );
         assert_eq!(
             it.next(),
-            Some(Command::WriteBurst(BurstCommand {
+            Some(Ok(Command::WriteBurst(BurstCommand {
                 data: &[0x5c, 0x0f, 0xa0],
-            }))
+            })))
         );
         assert_eq!(
             it.next(),
-            Some(Command::WriteSingle(RegisterWrite {
+            Some(Ok(Command::WriteSingle(RegisterWrite {
                 register: 0x5e,
                 value: 0x01
-            }))
+            })))
         );
     }
 
@@ -156,23 +297,89 @@ mod test {
 
         assert_eq!(
             it.next(),
-            Some(Command::WriteSingle(RegisterWrite {
+            Some(Ok(Command::WriteSingle(RegisterWrite {
                 register: 0x5a,
                 value: 0x0f
-            }))
+            })))
         );
         assert_eq!(
             it.next(),
-            Some(Command::WriteBurst(BurstCommand {
+            Some(Ok(Command::WriteBurst(BurstCommand {
                 data: &[0x5c, 0x0f, 0xa0, 0xc5],
-            }))
+            })))
         );
         assert_eq!(
             it.next(),
-            Some(Command::WriteSingle(RegisterWrite {
+            Some(Ok(Command::WriteSingle(RegisterWrite {
                 register: 0x5e,
                 value: 0x01
-            }))
+            })))
+        );
+    }
+
+    #[test]
+    fn commands_odd_length() {
+        let mut it = CommandIterator::new(&[0x5a, 0x0f, 0x5e]);
+
+        assert_eq!(
+            it.next(),
+            Some(Ok(Command::WriteSingle(RegisterWrite {
+                register: 0x5a,
+                value: 0x0f
+            })))
+        );
+        assert_eq!(it.next(), Some(Err(BulkError::OddLength)));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn commands_burst_truncated() {
+        assert_eq!(
+            validate(&[CFG_META_BURST, 0x03, 0x5c, 0x0f, 0xa0]),
+            Err(BulkError::Truncated)
+        );
+        // Missing zero-padding
+        assert_eq!(
+            validate(&[CFG_META_BURST, 0x02, 0x5c, 0x0f, 0xa0]),
+            Err(BulkError::Truncated)
+        );
+    }
+
+    #[test]
+    fn commands_burst_page_boundaries() {
+        assert_eq!(
+            validate(&[CFG_META_BURST, 0x03, 0x7e, 0x01, 0x02, 0x03]),
+            Err(BulkError::BurstOverrunsPage)
+        );
+        assert_eq!(
+            validate(&[CFG_META_BURST, 0x02, 0x7e, 0x01, 0x02, 0x00]),
+            Err(BulkError::BurstCrossesBook)
+        );
+        assert_eq!(validate(&[CFG_META_BURST, 0x01, 0x7e, 0x01]), Ok(()));
+    }
+
+    #[test]
+    fn commands_delay() {
+        let mut it = CommandIterator::new(&[0x01, 0x01, CFG_META_DELAY, 0x0a, 0x02, 0x00]);
+
+        assert_eq!(
+            it.next(),
+            Some(Ok(Command::WriteSingle(RegisterWrite {
+                register: 0x01,
+                value: 0x01
+            })))
+        );
+        assert_eq!(
+            it.next(),
+            Some(Ok(Command::Delay(DelayCommand { milliseconds: 10 })))
+        );
+        assert_eq!(
+            it.next(),
+            Some(Ok(Command::WriteSingle(RegisterWrite {
+                register: 0x02,
+                value: 0x00
+            })))
         );
+        assert_eq!(it.next(), None);
     }
 }
@@ -0,0 +1,47 @@
+//! Error type shared by all layers of this crate.
+
+use core::fmt::{Debug, Display, Formatter};
+
+use crate::bulk::BulkError;
+use crate::hl::clock::ClockState;
+
+/// Errors that can occur when communicating with the TAS2563.
+///
+/// `E` is the error type of the underlying bus.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// The underlying bus reported an error.
+    Bus(E),
+    /// A bulk command stream is malformed.
+    Bulk(BulkError),
+    /// A block of coefficients runs past the last page of its book.
+    CoefficientOutOfRange,
+    /// The pin connected to IRQZ reported an error, of the given kind.
+    Pin(embedded_hal::digital::ErrorKind),
+    /// The device did not complete an operation in time.
+    Timeout,
+    /// The clocks did not lock in time, with the last observed state.
+    ClockTimeout(ClockState),
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(value: E) -> Self {
+        Error::Bus(value)
+    }
+}
+
+impl<E: Debug> Display for Error<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Bus(e) => write!(f, "bus error: {:?}", e),
+            Error::Bulk(e) => write!(f, "invalid bulk command stream: {}", e),
+            Error::CoefficientOutOfRange => write!(f, "coefficient block runs past the book"),
+            Error::Pin(kind) => write!(f, "IRQZ pin error: {}", kind),
+            Error::Timeout => write!(f, "timed out"),
+            Error::ClockTimeout(state) => write!(f, "clocks did not lock in time: {:?}", state),
+        }
+    }
+}
+
+impl<E: Debug> core::error::Error for Error<E> {}
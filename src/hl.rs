@@ -1,6 +1,29 @@
 //! High level interface for the TAS2563 chipset providing convenience methods and a Rust-style interface.
+//!
+//! The configuration types fill in register writers and decode register reads themselves, such
+//! that this async interface and its [blocking] counterpart share them.
 
-use embedded_hal_async::{i2c::I2c, spi::SpiDevice};
+pub mod asi2;
+pub mod blocking;
+pub mod boost;
+pub mod bop;
+pub mod clock;
+pub mod config;
+pub mod derating;
+pub mod diagnostics;
+pub mod faults;
+pub mod limiter;
+pub mod monitor;
+pub mod pdm;
+pub mod recovery;
+pub mod sar;
+pub mod tdm;
+pub mod volume;
+
+#[cfg(test)]
+mod test;
+
+use embedded_hal_async::{delay::DelayNs, i2c::I2c, spi::SpiDevice};
 
 use crate::ll::{
     i2c::{Address, I2CInterface},
@@ -8,10 +31,28 @@ use crate::ll::{
     Tas2563Device, Tas2563Interface,
 };
 use crate::prelude::*;
+use crate::{bulk, Error};
+use asi2::Asi2Config;
+use boost::BoostConfig;
+use bop::{BopConfig, BOP_ADDRESS, BOP_COEFFICIENTS};
+use clock::{ClockState, Clocking, DetectedClocking, CLOCK_POLL_INTERVAL_MS, CLOCK_STABLE_POLLS};
+use config::Tas2563Config;
+use diagnostics::{LoadDiagnostics, LoadDiagnosticsConfig};
+use faults::Faults;
+use limiter::{LimiterConfig, LIMITER_ADDRESS, LIMITER_COEFFICIENTS, MAX_ATTENUATION_ADDRESS};
+use pdm::PdmConfig;
+use sar::SarReading;
+use tdm::TdmConfig;
+use volume::{volume_from_db, volume_from_linear, VolumeRamp, VOLUME_ADDRESS};
 
 /// High level interface for the TAS2563 chipset.
 pub struct Tas2563<T> {
     dev: Tas2563Device<T>,
+    /// Mode last written, `None` if unknown.
+    mode: Option<Mode>,
+    /// Mode to return to on [`Tas2563::wake`].
+    wake_mode: Mode,
+    mute_ramp: Option<VolumeRamp>,
 }
 
 impl<T> Tas2563<I2CInterface<T>>
@@ -19,9 +60,7 @@ where
     T: I2c,
 {
     pub fn new_i2c(i2c: T, address: Address) -> Self {
-        Self {
-            dev: Tas2563Device::new_i2c(i2c, address),
-        }
+        Self::from_device(Tas2563Device::new_i2c(i2c, address))
     }
 
     pub fn take(self) -> T {
@@ -34,9 +73,7 @@ where
     T: SpiDevice,
 {
     pub fn new_spi(spi: T) -> Self {
-        Self {
-            dev: Tas2563Device::new_spi(spi),
-        }
+        Self::from_device(Tas2563Device::new_spi(spi))
     }
 
     pub fn take(self) -> T {
@@ -45,41 +82,640 @@ where
 }
 
 impl<T> Tas2563<T> {
+    fn from_device(dev: Tas2563Device<T>) -> Self {
+        Self {
+            dev,
+            mode: None,
+            wake_mode: Mode::Mute,
+            mute_ramp: None,
+        }
+    }
+
     /// Get access to the underlying low level device.
+    ///
+    /// Changes of `pwr_ctl` made through it are not reflected by [`Tas2563::mode`], call
+    /// [`Tas2563::refresh_mode`] afterwards.
     pub fn ll(&mut self) -> &mut Tas2563Device<T> {
         &mut self.dev
     }
+
+    /// Mode of the device as last written, `None` if not known.
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
+
+    /// Ramp the digital volume when entering or leaving [`Mode::Active`], or switch abruptly when
+    /// `None` (the default).
+    pub fn set_mute_ramp(&mut self, ramp: Option<VolumeRamp>) {
+        self.mute_ramp = ramp;
+    }
 }
 
 impl<T> Tas2563<T>
 where
     T: Tas2563Interface,
 {
+    /// Power up and configure the device.
+    ///
+    /// Follows the recommended power-up order: software reset, software shutdown with the sense
+    /// blocks powered, configuration of all blocks, tuning blobs and finally the configured mode.
+    /// All tuning blobs are validated before the device is touched.
+    pub async fn init(
+        &mut self,
+        config: &Tas2563Config<'_>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        for blob in config.tuning {
+            bulk::validate(blob).map_err(Error::Bulk)?;
+        }
+
+        self.mode = None;
+        self.dev
+            .software_reset()
+            .write_async(|w| w.software_reset(true))
+            .await?;
+        // The reset also reverts the book and page.
+        self.dev.reset_assumptions();
+        delay.delay_ms(1).await;
+
+        self.dev
+            .pwr_ctl()
+            .write_async(|w| w.mode(Mode::SoftwareShutdown).vsns_pd(false).isns_pd(false))
+            .await?;
+
+        let dev = &mut self.dev;
+        dev.pb_cfg_1().write_async(|w| config.pb_cfg_1(w)).await?;
+        dev.misc_cfg_1()
+            .write_async(|w| config.protection.misc_cfg_1(w))
+            .await?;
+
+        self.set_tdm(&config.tdm).await?;
+
+        if let Some(limiter) = &config.limiter {
+            self.set_limiter(limiter).await?;
+        }
+
+        if let Some(bop) = &config.bop {
+            self.set_bop(bop).await?;
+        }
+
+        let dev = &mut self.dev;
+        let interrupts = &config.interrupts;
+        dev.int_clk_cfg()
+            .write_async(|w| interrupts.int_clk_cfg(w))
+            .await?;
+        dev.misc().write_async(|w| interrupts.misc(w)).await?;
+
+        self.set_boost(&config.boost).await?;
+        let dev = &mut self.dev;
+
+        let pdm = &config.pdm;
+        dev.pdm_config_0()
+            .write_async(|w| pdm.pdm_config_0(w))
+            .await?;
+        dev.din_pd_pdm_config_3()
+            .modify_async(|w| pdm.din_pd_pdm_config_3(w))
+            .await?;
+
+        for blob in config.tuning {
+            bulk::CommandIterator::new(blob).write(dev, delay).await?;
+        }
+
+        dev.pwr_ctl()
+            .write_async(|w| {
+                w.mode(config.mode)
+                    .vsns_pd(false)
+                    .isns_pd(false)
+                    .pdm_i_2_s_mode(pdm.i2s_mode)
+            })
+            .await?;
+        self.mode = Some(config.mode);
+
+        Ok(())
+    }
+
+    /// Configure the serial audio interface and the clocking derived from it.
+    ///
+    /// Preferably done in software shutdown, as all registers are written one by one.
+    pub async fn set_tdm(&mut self, tdm: &TdmConfig) -> Result<(), Error<T::Error>> {
+        let dev = &mut self.dev;
+        dev.tdm_cfg_0().write_async(|w| tdm.tdm_cfg_0(w)).await?;
+        dev.tdm_cfg_1().write_async(|w| tdm.tdm_cfg_1(w)).await?;
+        dev.tdm_cfg_2().write_async(|w| tdm.tdm_cfg_2(w)).await?;
+        dev.tdm_cfg_3().write_async(|w| tdm.tdm_cfg_3(w)).await?;
+        dev.tdm_cfg_4().write_async(|w| tdm.tdm_cfg_4(w)).await?;
+        dev.tdm_cfg_5().write_async(|w| tdm.tdm_cfg_5(w)).await?;
+        dev.tdm_cfg_6().write_async(|w| tdm.tdm_cfg_6(w)).await?;
+        dev.tdm_cfg_7().write_async(|w| tdm.tdm_cfg_7(w)).await?;
+        dev.tdm_cfg_8().write_async(|w| tdm.tdm_cfg_8(w)).await?;
+        dev.tdm_cfg_9().write_async(|w| tdm.tdm_cfg_9(w)).await?;
+        dev.tdm_cfg_10().write_async(|w| tdm.tdm_cfg_10(w)).await?;
+        dev.clock_configuration()
+            .write_async(|w| tdm.clock_configuration(w))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Configure the secondary audio serial interface.
+    pub async fn set_asi2(&mut self, asi2: &Asi2Config) -> Result<(), Error<T::Error>> {
+        let dev = &mut self.dev;
+        dev.asi_2_config_0()
+            .write_async(|w| asi2.asi_2_config_0(w))
+            .await?;
+        dev.asi_2_config_1()
+            .write_async(|w| asi2.asi_2_config_1(w))
+            .await?;
+        dev.asi_2_config_2()
+            .write_async(|w| asi2.asi_2_config_2(w))
+            .await?;
+        dev.asi_2_config_3()
+            .write_async(|w| asi2.asi_2_config_3(w))
+            .await?;
+        Ok(())
+    }
+
+    /// Read back the configuration of the secondary audio serial interface.
+    ///
+    /// The result is not validated, see [`Asi2Config::to_builder`] for that.
+    pub async fn asi2(&mut self) -> Result<Asi2Config, Error<T::Error>> {
+        Ok(Asi2Config::from_registers(
+            self.dev.asi_2_config_0().read_async().await?,
+            self.dev.asi_2_config_1().read_async().await?,
+            self.dev.asi_2_config_2().read_async().await?,
+            self.dev.asi_2_config_3().read_async().await?,
+        ))
+    }
+
+    /// Switch between automatic and manual clocking, leaving the rest of the layout untouched.
+    pub async fn set_clocking(&mut self, clocking: Clocking) -> Result<(), Error<T::Error>> {
+        self.dev
+            .tdm_cfg_0()
+            .modify_async(|w| clocking.tdm_cfg_0(w))
+            .await?;
+        self.dev
+            .clock_configuration()
+            .modify_async(|w| clocking.clock_configuration(w))
+            .await?;
+        Ok(())
+    }
+
+    /// Read back whether clocking is automatic or manual.
+    pub async fn clocking(&mut self) -> Result<Clocking, Error<T::Error>> {
+        Ok(Clocking::from_registers(
+            self.dev.tdm_cfg_0().read_async().await?,
+            self.dev.clock_configuration().read_async().await?,
+        ))
+    }
+
+    /// Sample rate and SBCLK to FSYNC ratio as detected on the serial audio interface.
+    ///
+    /// Allows verifying the clocking provided by the bus master, for example before unmuting.
+    pub async fn detected_clocking(&mut self) -> Result<DetectedClocking, Error<T::Error>> {
+        let dsp_mode_tdm_det = self.dev.dsp_mode_tdm_det().read_async().await?;
+        Ok(DetectedClocking::from_register(dsp_mode_tdm_det))
+    }
+
+    /// Configure the VBAT tracking limiter, both its timing and its coefficients.
+    pub async fn set_limiter(&mut self, limiter: &LimiterConfig) -> Result<(), Error<T::Error>> {
+        self.dev
+            .lim_cfg_0()
+            .write_async(|w| limiter.lim_cfg_0(w))
+            .await?;
+        self.dev
+            .lim_cfg_1()
+            .write_async(|w| limiter.lim_cfg_1(w))
+            .await?;
+        self.dev
+            .write_coefficients(LIMITER_ADDRESS, &limiter.to_coefficients())
+            .await
+    }
+
+    /// Read back the configuration of the VBAT tracking limiter.
+    pub async fn limiter(&mut self) -> Result<LimiterConfig, Error<T::Error>> {
+        let lim_cfg_0 = self.dev.lim_cfg_0().read_async().await?;
+        let lim_cfg_1 = self.dev.lim_cfg_1().read_async().await?;
+        let mut coefficients = [0; LIMITER_COEFFICIENTS];
+        self.dev
+            .read_coefficients(LIMITER_ADDRESS, &mut coefficients)
+            .await?;
+        Ok(LimiterConfig::from_registers(
+            lim_cfg_0,
+            lim_cfg_1,
+            coefficients,
+        ))
+    }
+
+    /// Configure brown out prevention, both its timing and its coefficients.
+    pub async fn set_bop(&mut self, bop: &BopConfig) -> Result<(), Error<T::Error>> {
+        self.dev
+            .dsp_frequency_bop_cfg_0()
+            .write_async(|w| bop.dsp_frequency_bop_cfg_0(w))
+            .await?;
+        self.dev
+            .bop_cfg_0()
+            .write_async(|w| bop.bop_cfg_0(w))
+            .await?;
+        if let Some(word) = bop.max_attenuation_coefficient() {
+            self.dev
+                .write_coefficient(MAX_ATTENUATION_ADDRESS, word)
+                .await?;
+        }
+        self.dev
+            .write_coefficients(BOP_ADDRESS, &bop.to_coefficients())
+            .await
+    }
+
+    /// Read back the configuration of brown out prevention.
+    pub async fn bop(&mut self) -> Result<BopConfig, Error<T::Error>> {
+        let dsp_frequency_bop_cfg_0 = self.dev.dsp_frequency_bop_cfg_0().read_async().await?;
+        let bop_cfg_0 = self.dev.bop_cfg_0().read_async().await?;
+        let mut coefficients = [0; BOP_COEFFICIENTS];
+        self.dev
+            .read_coefficients(BOP_ADDRESS, &mut coefficients)
+            .await?;
+        let max_attenuation = self.dev.read_coefficient(MAX_ATTENUATION_ADDRESS).await?;
+        Ok(BopConfig::from_registers(
+            dsp_frequency_bop_cfg_0,
+            bop_cfg_0,
+            coefficients,
+            max_attenuation,
+        ))
+    }
+
+    /// Configure the boost converter.
+    pub async fn set_boost(&mut self, boost: &BoostConfig) -> Result<(), Error<T::Error>> {
+        self.dev
+            .boost_cfg_1()
+            .write_async(|w| boost.boost_cfg_1(w))
+            .await?;
+        self.dev
+            .boost_cfg_2()
+            .write_async(|w| boost.boost_cfg_2(w))
+            .await?;
+        self.dev
+            .boost_cfg_3()
+            .write_async(|w| boost.boost_cfg_3(w))
+            .await?;
+        self.dev
+            .bst_ilim_cfg_0()
+            .write_async(|w| boost.bst_ilim_cfg_0(w))
+            .await?;
+        Ok(())
+    }
+
+    /// Read back the configuration of the boost converter, as programmed in the device.
+    pub async fn boost(&mut self) -> Result<BoostConfig, Error<T::Error>> {
+        Ok(BoostConfig::from_registers(
+            self.dev.boost_cfg_1().read_async().await?,
+            self.dev.boost_cfg_2().read_async().await?,
+            self.dev.boost_cfg_3().read_async().await?,
+            self.dev.bst_ilim_cfg_0().read_async().await?,
+        ))
+    }
+
+    /// Configure the PDM microphone interface.
+    pub async fn set_pdm(&mut self, pdm: &PdmConfig) -> Result<(), Error<T::Error>> {
+        self.dev
+            .pdm_config_0()
+            .write_async(|w| pdm.pdm_config_0(w))
+            .await?;
+        self.dev
+            .din_pd_pdm_config_3()
+            .modify_async(|w| pdm.din_pd_pdm_config_3(w))
+            .await?;
+        self.dev
+            .pwr_ctl()
+            .modify_async(|w| w.pdm_i_2_s_mode(pdm.i2s_mode))
+            .await?;
+        Ok(())
+    }
+
+    /// Read back the configuration of the PDM microphone interface.
+    pub async fn pdm(&mut self) -> Result<PdmConfig, Error<T::Error>> {
+        Ok(PdmConfig::from_registers(
+            self.dev.pdm_config_0().read_async().await?,
+            self.dev.din_pd_pdm_config_3().read_async().await?,
+            self.dev.pwr_ctl().read_async().await?,
+        ))
+    }
+
+    /// Read the interrupt sources that fired since the latches were last cleared.
+    pub async fn latched_faults(&mut self) -> Result<Faults, Error<T::Error>> {
+        Ok(Faults::from_registers([
+            self.dev.int_ltch_0().read_async().await?.value(),
+            self.dev.int_ltch_1().read_async().await?.value(),
+            self.dev.int_ltch_3().read_async().await?.value(),
+            self.dev.int_ltch_4().read_async().await?.value(),
+        ]))
+    }
+
+    /// Read the interrupt sources that are currently active.
+    pub async fn live_faults(&mut self) -> Result<Faults, Error<T::Error>> {
+        Ok(Faults::from_registers([
+            self.dev.int_live_0().read_async().await?.value(),
+            self.dev.int_live_1().read_async().await?.value(),
+            self.dev.int_live_3().read_async().await?.value(),
+            self.dev.int_live_4().read_async().await?.value(),
+        ]))
+    }
+
+    /// Prevent the `masked` interrupt sources from asserting IRQZ, and allow all others.
+    pub async fn set_interrupt_mask(&mut self, masked: Faults) -> Result<(), Error<T::Error>> {
+        let [m0, m1, m2, m3] = masked.to_mask_registers();
+        self.dev.int_mask_0().write_async(|w| w.value(m0)).await?;
+        self.dev.int_mask_1().write_async(|w| w.value(m1)).await?;
+        self.dev.int_mask_2().write_async(|w| w.value(m2)).await?;
+        self.dev.int_mask_3().write_async(|w| w.value(m3)).await?;
+        Ok(())
+    }
+
+    /// Clear all latched interrupt sources, releasing IRQZ when configured as latched.
+    pub async fn clear_latched(&mut self) -> Result<(), Error<T::Error>> {
+        self.dev
+            .int_clk_cfg()
+            .modify_async(|w| w.clr_intp_ltch(true))
+            .await
+    }
+
+    /// Set the analog gain of the amplifier to the level nearest to `dbv`.
+    ///
+    /// See [`AmpLevel::from_dbv`] for rounding and clamping, returns the level that was applied.
+    pub async fn set_gain_db(&mut self, dbv: f32) -> Result<AmpLevel, Error<T::Error>> {
+        let level = AmpLevel::from_dbv(dbv);
+        self.dev
+            .pb_cfg_1()
+            .modify_async(|w| w.amp_level(level))
+            .await?;
+        Ok(level)
+    }
+
+    /// Read back the analog gain of the amplifier in dBV, `None` if the level is not a valid one.
+    pub async fn gain_db(&mut self) -> Result<Option<f32>, Error<T::Error>> {
+        Ok(self.dev.pb_cfg_1().read_async().await?.amp_level().to_dbv())
+    }
+
+    /// Set the digital volume in dB, see [`volume::volume_from_db`] for clamping and mute.
+    pub async fn set_volume_db(&mut self, db: f32) -> Result<(), Error<T::Error>> {
+        self.dev
+            .write_coefficient(VOLUME_ADDRESS, volume_from_db(db).0)
+            .await
+    }
+
+    /// Set the digital volume as a linear gain, see [`volume::volume_from_linear`].
+    pub async fn set_volume_linear(&mut self, gain: f32) -> Result<(), Error<T::Error>> {
+        self.dev
+            .write_coefficient(VOLUME_ADDRESS, volume_from_linear(gain).0)
+            .await
+    }
+
+    /// Read back the digital volume in dB, negative infinity when muted.
+    pub async fn volume_db(&mut self) -> Result<f32, Error<T::Error>> {
+        Ok(Q2_30(self.dev.read_coefficient(VOLUME_ADDRESS).await?).to_db())
+    }
+
+    /// Gradually change the digital volume from its current value to `db`.
+    ///
+    /// Muting ramps down to [`volume::VOLUME_MIN_DB`] before muting, unmuting starts from there.
+    pub async fn ramp_volume_db(
+        &mut self,
+        db: f32,
+        ramp: &VolumeRamp,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let from = self.volume_db().await?;
+        for step in ramp.steps(from, db) {
+            self.set_volume_db(step).await?;
+            delay.delay_ms(ramp.interval_ms).await;
+        }
+        self.set_volume_db(db).await
+    }
+
+    /// Check whether a speaker is connected, returning to the previous mode afterwards.
+    ///
+    /// Latched faults are cleared before and after the measurement. The previous mode is also
+    /// restored when the measurement fails, for example with [`Error::Timeout`] should no result
+    /// be reported in time.
+    pub async fn load_diagnostics(
+        &mut self,
+        config: &LoadDiagnosticsConfig,
+        delay: &mut impl DelayNs,
+    ) -> Result<LoadDiagnostics, Error<T::Error>> {
+        let previous = match self.mode {
+            Some(mode) => mode,
+            None => self.refresh_mode().await?,
+        };
+
+        self.dev
+            .hold_sar_update()
+            .modify_async(|w| {
+                w.averaging_timer_init_ldg_mode_reg(config.averaging)
+                    .switch_aux_scheme_ldg_reg(config.aux_clock)
+            })
+            .await?;
+        self.clear_latched().await?;
+
+        let result = self.measure_load(config, delay).await;
+
+        self.write_mode(previous).await?;
+        self.clear_latched().await?;
+
+        let (faults, ohms) = result?;
+        Ok(config.evaluate(faults, ohms))
+    }
+
+    /// Enter load diagnostics and wait for its result, leaving the device in that mode.
+    async fn measure_load(
+        &mut self,
+        config: &LoadDiagnosticsConfig,
+        delay: &mut impl DelayNs,
+    ) -> Result<(Faults, Option<f32>), Error<T::Error>> {
+        self.mode = None;
+        self.dev
+            .pwr_ctl()
+            .modify_async(|w| w.mode(Mode::LoadDiagnosticsActive))
+            .await?;
+
+        let interval = config.poll_interval_ms.max(1);
+        let mut waited = 0;
+        let faults = loop {
+            delay.delay_ms(interval).await;
+            waited += interval;
+
+            let faults = self.latched_faults().await?;
+            if faults
+                .intersects(Faults::LOAD_DIAGNOSTIC_DONE | Faults::OPEN_LOAD | Faults::SHORT_LOAD)
+            {
+                break faults;
+            }
+            if waited >= config.timeout_ms {
+                return Err(Error::Timeout);
+            }
+        };
+
+        let ohms = match config.impedance {
+            Some(address) => Some(diagnostics::impedance_ohms(
+                self.dev.read_coefficient(address).await?,
+            )),
+            None => None,
+        };
+
+        Ok((faults, ohms))
+    }
+
+    /// Set the analog gain and unmute, or mute when `level` is `None`.
+    ///
+    /// Does not ramp, see [`Tas2563::mute`] and [`Tas2563::unmute`] for that.
     pub async fn amplification_level_or_mute(
         &mut self,
         level: Option<AmpLevel>,
-    ) -> Result<(), T::Error> {
+    ) -> Result<(), Error<T::Error>> {
         if let Some(level) = level {
-            self.dev
-                .pb_cfg_1()
-                .write_async(|w| w.amp_level(level))
-                .await?;
+            if self.dev.pb_cfg_1().read_async().await?.amp_level() != level {
+                self.dev
+                    .pb_cfg_1()
+                    .modify_async(|w| w.amp_level(level))
+                    .await?;
+            }
+            self.write_mode(Mode::Active).await
+        } else {
+            self.write_mode(Mode::Mute).await
+        }
+    }
 
-            self.dev
-                .pwr_ctl()
-                .modify_async(|w| w.mode(Mode::Active))
-                .await?;
+    /// Enter [`Mode::Mute`], ramping down the volume first if configured.
+    pub async fn mute(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(Mode::Mute, delay).await
+    }
+
+    /// Enter [`Mode::Active`], ramping up the volume afterwards if configured.
+    pub async fn unmute(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(Mode::Active, delay).await
+    }
+
+    /// Wait for the clocks of the serial audio interface to lock, then unmute.
+    ///
+    /// Unmuting without clocks raises TDM clock errors and pops. When the clocks do not lock
+    /// within `timeout_ms`, the mode is left as is and [`Error::ClockTimeout`] is returned.
+    pub async fn unmute_when_locked(
+        &mut self,
+        timeout_ms: u32,
+        delay: &mut impl DelayNs,
+    ) -> Result<ClockState, Error<T::Error>> {
+        let mut waited = 0;
+        let mut stable = 0;
+        loop {
+            let state = self.clock_state().await?;
+            stable = if state.is_locked() { stable + 1 } else { 0 };
+            if stable >= CLOCK_STABLE_POLLS {
+                self.unmute(delay).await?;
+                return Ok(state);
+            }
+            if waited >= timeout_ms {
+                return Err(Error::ClockTimeout(state));
+            }
+
+            delay.delay_ms(CLOCK_POLL_INTERVAL_MS).await;
+            waited += CLOCK_POLL_INTERVAL_MS;
+        }
+    }
+
+    /// Read the clock detection and the clock related interrupt sources.
+    pub async fn clock_state(&mut self) -> Result<ClockState, Error<T::Error>> {
+        Ok(ClockState {
+            detected: self.detected_clocking().await?,
+            tdm_clock_error: self.dev.int_live_0().read_async().await?.tdm_clock(),
+            pll_lock_error: self.dev.int_live_3().read_async().await?.pll_lock(),
+        })
+    }
+
+    /// Enter [`Mode::SoftwareShutdown`], remembering the current mode for [`Tas2563::wake`].
+    pub async fn shutdown(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        match self.mode {
+            Some(Mode::SoftwareShutdown) => return Ok(()),
+            Some(mode) => self.wake_mode = mode,
+            None => {}
+        }
+        self.set_mode(Mode::SoftwareShutdown, delay).await
+    }
+
+    /// Return to the mode before [`Tas2563::shutdown`], or [`Mode::Mute`] if not known.
+    pub async fn wake(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(self.wake_mode, delay).await
+    }
+
+    /// Read the mode back from the device, for after changing it through [`Tas2563::ll`].
+    pub async fn refresh_mode(&mut self) -> Result<Mode, Error<T::Error>> {
+        let mode = self.dev.pwr_ctl().read_async().await?.mode();
+        self.mode = Some(mode);
+        Ok(mode)
+    }
+
+    async fn set_mode(
+        &mut self,
+        mode: Mode,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let Some(ramp) = self.mute_ramp.clone() else {
+            return self.write_mode(mode).await;
+        };
+
+        if self.mode == Some(mode) {
+            Ok(())
+        } else if mode == Mode::Active {
+            // Start silent and restore the volume once active.
+            let volume = self.volume_db().await?;
+            self.set_volume_db(f32::NEG_INFINITY).await?;
+            self.write_mode(mode).await?;
+            self.ramp_volume_db(volume, &ramp, delay).await
+        } else if self.mode == Some(Mode::Active) {
+            // Fade out and restore the volume once inaudible.
+            let volume = self.volume_db().await?;
+            self.ramp_volume_db(f32::NEG_INFINITY, &ramp, delay).await?;
+            self.write_mode(mode).await?;
+            self.set_volume_db(volume).await
         } else {
-            self.dev
-                .pwr_ctl()
-                .modify_async(|w| w.mode(Mode::Mute))
-                .await?;
+            self.write_mode(mode).await
         }
+    }
 
+    /// Write the mode unless it is already known to be set.
+    async fn write_mode(&mut self, mode: Mode) -> Result<(), Error<T::Error>> {
+        if self.mode == Some(mode) {
+            return Ok(());
+        }
+
+        // Unknown should a write fail half-way.
+        self.mode = None;
+        self.dev.pwr_ctl().modify_async(|w| w.mode(mode)).await?;
+        self.mode = Some(mode);
         Ok(())
     }
 
-    pub async fn adc(&mut self) -> Result<ADCReadout, T::Error> {
+    /// Read PVDD, VBAT and the die temperature, holding the conversions such that they belong
+    /// together.
+    pub async fn sar(&mut self) -> Result<SarReading, Error<T::Error>> {
+        self.dev
+            .hold_sar_update()
+            .modify_async(|w| w.sar_data_hold_update(true))
+            .await?;
+        let reading = async {
+            Ok(SarReading {
+                pvdd: self.dev.pvdd().read_async().await?.pvdd_cnv_dsp().into(),
+                vbat: self.dev.vbat().read_async().await?.vbat_cnv().into(),
+                temperature: self.dev.temp().read_async().await?.tmp_cnv().into(),
+            })
+        }
+        .await;
+        // Release the hold regardless, lest the readback stays frozen.
+        self.dev
+            .hold_sar_update()
+            .modify_async(|w| w.sar_data_hold_update(false))
+            .await?;
+        reading
+    }
+
+    pub async fn adc(&mut self) -> Result<ADCReadout, Error<T::Error>> {
         Ok(ADCReadout {
             pvdd: self.dev.pvdd().read_async().await?.pvdd_cnv_dsp(),
             vbat: self.dev.vbat().read_async().await?.vbat_cnv(),
@@ -0,0 +1,375 @@
+//! Secondary audio serial interface (ASI2).
+//!
+//! ASI2 can share its bus with up to three other devices, each transmitting in its own group of
+//! slots according to its device number. An [`Asi2Config`] can only be obtained through
+//! [`Asi2ConfigBuilder::build`], which rejects arrangements the device cannot encode.
+
+use crate::ll::registers::{asi_2_config_0, asi_2_config_1, asi_2_config_2, asi_2_config_3};
+use crate::prelude::*;
+
+/// Highest RX offset in SBCLK cycles.
+const RX_OFFSET_MAX: u8 = 31;
+/// Highest TX offset in SBCLK cycles.
+const TX_OFFSET_MAX: u8 = 7;
+/// Most devices that can share the bus.
+const DEVICES_MAX: u8 = 4;
+/// Most slots per device.
+const SLOTS_MAX: u8 = 2;
+
+/// Reasons an ASI2 configuration can be rejected.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Asi2Error {
+    /// The SBCLK to FSYNC ratio is not one supported by the device.
+    ReservedSbclkFsRatio,
+    /// The RX offset exceeds 31 SBCLK cycles.
+    RxOffsetOutOfRange,
+    /// The TX offset exceeds 7 SBCLK cycles.
+    TxOffsetOutOfRange,
+    /// The amount of devices on the bus is not between 1 and 4.
+    DevicesOutOfRange,
+    /// The device number is not below the amount of devices on the bus.
+    DeviceNumberOutOfRange,
+    /// The amount of slots per device is neither 1 nor 2.
+    SlotsOutOfRange,
+}
+
+impl core::fmt::Display for Asi2Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Asi2Error::ReservedSbclkFsRatio => write!(f, "reserved SBCLK to FSYNC ratio"),
+            Asi2Error::RxOffsetOutOfRange => {
+                write!(f, "RX offset exceeds {} cycles", RX_OFFSET_MAX)
+            }
+            Asi2Error::TxOffsetOutOfRange => {
+                write!(f, "TX offset exceeds {} cycles", TX_OFFSET_MAX)
+            }
+            Asi2Error::DevicesOutOfRange => {
+                write!(f, "amount of devices not between 1 and {}", DEVICES_MAX)
+            }
+            Asi2Error::DeviceNumberOutOfRange => {
+                write!(f, "device number exceeds the amount of devices")
+            }
+            Asi2Error::SlotsOutOfRange => {
+                write!(f, "slots per device not between 1 and {}", SLOTS_MAX)
+            }
+        }
+    }
+}
+
+impl core::error::Error for Asi2Error {}
+
+/// Validated configuration of the secondary audio serial interface.
+///
+/// The default describes a single device slaved to a 64 SBCLK cycle frame, with one slot.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Asi2Config {
+    sbclk_fs_ratio: SbclkFsRatio,
+    tx_fill: TxFill,
+    master: bool,
+    tx_edge: TxEdge,
+    rx_edge: RxEdge,
+    tx_lsb_half_cycle: bool,
+    auto_rate: bool,
+    rx_offset: u8,
+    tx_offset: u8,
+    device_number: u8,
+    devices: u8,
+    slots_per_device: u8,
+    bus_keeper: bool,
+    tx_keeper: bool,
+}
+
+impl Default for Asi2Config {
+    fn default() -> Self {
+        Self {
+            sbclk_fs_ratio: SbclkFsRatio::Ratio64,
+            tx_fill: TxFill::Transmit0,
+            master: false,
+            tx_edge: TxEdge::RisingEdge,
+            rx_edge: RxEdge::RisingEdge,
+            tx_lsb_half_cycle: false,
+            auto_rate: true,
+            rx_offset: 0,
+            tx_offset: 0,
+            device_number: 0,
+            devices: 1,
+            slots_per_device: 1,
+            bus_keeper: false,
+            tx_keeper: false,
+        }
+    }
+}
+
+impl Asi2Config {
+    /// Start a configuration from the defaults.
+    pub fn builder() -> Asi2ConfigBuilder {
+        Asi2ConfigBuilder {
+            config: Self::default(),
+        }
+    }
+
+    /// Continue from this configuration.
+    pub fn to_builder(&self) -> Asi2ConfigBuilder {
+        Asi2ConfigBuilder {
+            config: self.clone(),
+        }
+    }
+
+    /// Length of a frame in ASI2 SBCLK cycles.
+    pub fn sbclk_fs_ratio(&self) -> SbclkFsRatio {
+        self.sbclk_fs_ratio
+    }
+
+    /// Whether the device drives ASI2 SBCLK.
+    pub fn master(&self) -> bool {
+        self.master
+    }
+
+    /// Number of this device on the shared bus.
+    pub fn device_number(&self) -> u8 {
+        self.device_number
+    }
+
+    /// Amount of devices sharing the bus.
+    pub fn devices(&self) -> u8 {
+        self.devices
+    }
+
+    pub fn slots_per_device(&self) -> u8 {
+        self.slots_per_device
+    }
+
+    /// Whether the device detects the ASI2 sample rate.
+    pub fn auto_rate(&self) -> bool {
+        self.auto_rate
+    }
+
+    fn validate(&self) -> Result<(), Asi2Error> {
+        if let SbclkFsRatio::Reserved(_) = self.sbclk_fs_ratio {
+            return Err(Asi2Error::ReservedSbclkFsRatio);
+        }
+        if self.rx_offset > RX_OFFSET_MAX {
+            return Err(Asi2Error::RxOffsetOutOfRange);
+        }
+        if self.tx_offset > TX_OFFSET_MAX {
+            return Err(Asi2Error::TxOffsetOutOfRange);
+        }
+        if !(1..=DEVICES_MAX).contains(&self.devices) {
+            return Err(Asi2Error::DevicesOutOfRange);
+        }
+        if self.device_number >= self.devices {
+            return Err(Asi2Error::DeviceNumberOutOfRange);
+        }
+        if !(1..=SLOTS_MAX).contains(&self.slots_per_device) {
+            return Err(Asi2Error::SlotsOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    /// Configuration as read back from the device, which is not necessarily valid.
+    pub(crate) fn from_registers(
+        asi_2_config_0: asi_2_config_0::R,
+        asi_2_config_1: asi_2_config_1::R,
+        asi_2_config_2: asi_2_config_2::R,
+        asi_2_config_3: asi_2_config_3::R,
+    ) -> Self {
+        Self {
+            sbclk_fs_ratio: SbclkFsRatio::from(asi_2_config_0.asi_2_sbclk_fs_ratio()),
+            tx_fill: TxFill::from(asi_2_config_0.tx_fill_asi_2() as u8),
+            master: asi_2_config_1.asi_2_sbclk_master() == Asi2SbclkMaster::MasterMode,
+            tx_edge: TxEdge::from(asi_2_config_1.tx_edge_asi_2() as u8),
+            rx_edge: RxEdge::from(asi_2_config_1.rx_edge_asi_2() as u8),
+            tx_lsb_half_cycle: asi_2_config_1.asi_2_tx_lsb_half_cycle_reg(),
+            auto_rate: !asi_2_config_1.asi_2_auto_rate(),
+            rx_offset: asi_2_config_2.rx_offset_asi_2(),
+            tx_offset: asi_2_config_2.tx_offset_asi_2(),
+            device_number: asi_2_config_3.my_device_num(),
+            devices: asi_2_config_3.num_devices() + 1,
+            slots_per_device: asi_2_config_3.num_slots() + 1,
+            bus_keeper: asi_2_config_3.asi_2_sdout_bus_keeper_always_en(),
+            tx_keeper: asi_2_config_3.asi_2_tx_keeper(),
+        }
+    }
+}
+
+/// Builder for [`Asi2Config`], see [`Asi2Config::builder`].
+#[derive(Debug, Clone)]
+pub struct Asi2ConfigBuilder {
+    config: Asi2Config,
+}
+
+impl Asi2ConfigBuilder {
+    /// Check the configuration as a whole.
+    pub fn build(self) -> Result<Asi2Config, Asi2Error> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    /// Length of a frame in ASI2 SBCLK cycles.
+    pub fn sbclk_fs_ratio(mut self, ratio: SbclkFsRatio) -> Self {
+        self.config.sbclk_fs_ratio = ratio;
+        self
+    }
+
+    pub fn tx_fill(mut self, fill: TxFill) -> Self {
+        self.config.tx_fill = fill;
+        self
+    }
+
+    /// Drive ASI2 SBCLK rather than receiving it.
+    pub fn master(mut self, master: bool) -> Self {
+        self.config.master = master;
+        self
+    }
+
+    pub fn tx_edge(mut self, edge: TxEdge) -> Self {
+        self.config.tx_edge = edge;
+        self
+    }
+
+    pub fn rx_edge(mut self, edge: RxEdge) -> Self {
+        self.config.rx_edge = edge;
+        self
+    }
+
+    /// Drive the LSB of each transmitted word for only half a cycle.
+    pub fn tx_lsb_half_cycle(mut self, half: bool) -> Self {
+        self.config.tx_lsb_half_cycle = half;
+        self
+    }
+
+    /// Let the device detect the ASI2 sample rate, enabled by default.
+    pub fn auto_rate(mut self, auto: bool) -> Self {
+        self.config.auto_rate = auto;
+        self
+    }
+
+    /// Offset of slot 0 with respect to the frame start, in SBCLK cycles.
+    pub fn rx_offset(mut self, offset: u8) -> Self {
+        self.config.rx_offset = offset;
+        self
+    }
+
+    /// Offset of slot 0 with respect to the frame start, in SBCLK cycles.
+    pub fn tx_offset(mut self, offset: u8) -> Self {
+        self.config.tx_offset = offset;
+        self
+    }
+
+    /// Share the bus with other devices, this device being number `device_number` of `devices`.
+    pub fn shared_bus(mut self, device_number: u8, devices: u8) -> Self {
+        self.config.device_number = device_number;
+        self.config.devices = devices;
+        self
+    }
+
+    pub fn slots_per_device(mut self, slots: u8) -> Self {
+        self.config.slots_per_device = slots;
+        self
+    }
+
+    /// Always enable the bus keeper on ASI2 SDOUT.
+    pub fn bus_keeper(mut self, keeper: bool) -> Self {
+        self.config.bus_keeper = keeper;
+        self
+    }
+
+    /// Enable the bus keeper on ASI2 SDOUT around transmitted slots.
+    pub fn tx_keeper(mut self, keeper: bool) -> Self {
+        self.config.tx_keeper = keeper;
+        self
+    }
+}
+
+impl Asi2Config {
+    pub(crate) fn asi_2_config_0<'w>(
+        &self,
+        w: &'w mut asi_2_config_0::W,
+    ) -> &'w mut asi_2_config_0::W {
+        w.asi_2_sbclk_fs_ratio(self.sbclk_fs_ratio.into())
+            .tx_fill_asi_2(self.tx_fill == TxFill::TransmitHighZ)
+    }
+
+    pub(crate) fn asi_2_config_1<'w>(
+        &self,
+        w: &'w mut asi_2_config_1::W,
+    ) -> &'w mut asi_2_config_1::W {
+        w.asi_2_sbclk_master(if self.master {
+            Asi2SbclkMaster::MasterMode
+        } else {
+            Asi2SbclkMaster::SlaveMode
+        })
+        .tx_edge_asi_2(self.tx_edge == TxEdge::FallingEdge)
+        .rx_edge_asi_2(self.rx_edge == RxEdge::FallingEdge)
+        .asi_2_tx_lsb_half_cycle_reg(self.tx_lsb_half_cycle)
+        .asi_2_auto_rate(!self.auto_rate)
+    }
+
+    pub(crate) fn asi_2_config_2<'w>(
+        &self,
+        w: &'w mut asi_2_config_2::W,
+    ) -> &'w mut asi_2_config_2::W {
+        w.rx_offset_asi_2(self.rx_offset)
+            .tx_offset_asi_2(self.tx_offset)
+    }
+
+    pub(crate) fn asi_2_config_3<'w>(
+        &self,
+        w: &'w mut asi_2_config_3::W,
+    ) -> &'w mut asi_2_config_3::W {
+        w.my_device_num(self.device_number)
+            .num_devices(self.devices - 1)
+            .num_slots(self.slots_per_device - 1)
+            .asi_2_sdout_bus_keeper_always_en(self.bus_keeper)
+            .asi_2_tx_keeper(self.tx_keeper)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_is_valid() {
+        assert_eq!(Asi2Config::builder().build(), Ok(Asi2Config::default()));
+    }
+
+    #[test]
+    fn shared_bus() {
+        let config = Asi2Config::builder().shared_bus(3, 4).build().unwrap();
+        assert_eq!((config.device_number(), config.devices()), (3, 4));
+
+        assert_eq!(
+            Asi2Config::builder().shared_bus(2, 2).build(),
+            Err(Asi2Error::DeviceNumberOutOfRange)
+        );
+        assert_eq!(
+            Asi2Config::builder().shared_bus(0, 5).build(),
+            Err(Asi2Error::DevicesOutOfRange)
+        );
+        assert_eq!(
+            Asi2Config::builder().slots_per_device(3).build(),
+            Err(Asi2Error::SlotsOutOfRange)
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            Asi2Config::builder()
+                .sbclk_fs_ratio(SbclkFsRatio::Reserved(0x0f))
+                .build(),
+            Err(Asi2Error::ReservedSbclkFsRatio)
+        );
+        assert_eq!(
+            Asi2Config::builder().rx_offset(32).build(),
+            Err(Asi2Error::RxOffsetOutOfRange)
+        );
+        assert_eq!(
+            Asi2Config::builder().tx_offset(8).build(),
+            Err(Asi2Error::TxOffsetOutOfRange)
+        );
+    }
+}
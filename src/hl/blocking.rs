@@ -0,0 +1,602 @@
+//! Blocking counterpart of the high level interface, for use without an async executor.
+
+use embedded_hal::{delay::DelayNs, i2c::I2c, spi::SpiDevice};
+
+use super::{
+    asi2::Asi2Config,
+    boost::BoostConfig,
+    bop::{BopConfig, BOP_ADDRESS, BOP_COEFFICIENTS},
+    clock::{ClockState, Clocking, DetectedClocking, CLOCK_POLL_INTERVAL_MS, CLOCK_STABLE_POLLS},
+    config::Tas2563Config,
+    diagnostics::{self, LoadDiagnostics, LoadDiagnosticsConfig},
+    faults::Faults,
+    limiter::{LimiterConfig, LIMITER_ADDRESS, LIMITER_COEFFICIENTS, MAX_ATTENUATION_ADDRESS},
+    pdm::PdmConfig,
+    sar::SarReading,
+    tdm::TdmConfig,
+    volume::{volume_from_db, volume_from_linear, VolumeRamp, VOLUME_ADDRESS},
+};
+use crate::ll::{
+    blocking::{i2c::I2CInterface, spi::SPIInterface, Tas2563Interface},
+    i2c::Address,
+    Tas2563Device,
+};
+use crate::prelude::*;
+use crate::{bulk, Error};
+
+/// Blocking high level interface for the TAS2563 chipset.
+pub struct Tas2563<T> {
+    dev: Tas2563Device<T>,
+    /// Mode last written, `None` if unknown.
+    pub(super) mode: Option<Mode>,
+    /// Mode to return to on [`Tas2563::wake`].
+    wake_mode: Mode,
+    mute_ramp: Option<VolumeRamp>,
+}
+
+impl<T> Tas2563<I2CInterface<T>>
+where
+    T: I2c,
+{
+    pub fn new_i2c(i2c: T, address: Address) -> Self {
+        Self::from_device(Tas2563Device::new_blocking_i2c(i2c, address))
+    }
+
+    pub fn take(self) -> T {
+        self.dev.take()
+    }
+}
+
+impl<T> Tas2563<SPIInterface<T>>
+where
+    T: SpiDevice,
+{
+    pub fn new_spi(spi: T) -> Self {
+        Self::from_device(Tas2563Device::new_blocking_spi(spi))
+    }
+
+    pub fn take(self) -> T {
+        self.dev.take()
+    }
+}
+
+impl<T> Tas2563<T> {
+    fn from_device(dev: Tas2563Device<T>) -> Self {
+        Self {
+            dev,
+            mode: None,
+            wake_mode: Mode::Mute,
+            mute_ramp: None,
+        }
+    }
+
+    /// Get access to the underlying low level device.
+    ///
+    /// Changes of `pwr_ctl` made through it are not reflected by [`Tas2563::mode`], call
+    /// [`Tas2563::refresh_mode`] afterwards.
+    pub fn ll(&mut self) -> &mut Tas2563Device<T> {
+        &mut self.dev
+    }
+
+    /// Mode of the device as last written, `None` if not known.
+    pub fn mode(&self) -> Option<Mode> {
+        self.mode
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_mute_ramp`].
+    pub fn set_mute_ramp(&mut self, ramp: Option<VolumeRamp>) {
+        self.mute_ramp = ramp;
+    }
+}
+
+impl<T> Tas2563<T>
+where
+    T: Tas2563Interface,
+{
+    /// Blocking counterpart of [`super::Tas2563::init`].
+    pub fn init(
+        &mut self,
+        config: &Tas2563Config<'_>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        for blob in config.tuning {
+            bulk::validate(blob).map_err(Error::Bulk)?;
+        }
+
+        self.mode = None;
+        self.dev
+            .software_reset()
+            .write(|w| w.software_reset(true))?;
+        // The reset also reverts the book and page.
+        self.dev.reset_assumptions();
+        delay.delay_ms(1);
+
+        self.dev
+            .pwr_ctl()
+            .write(|w| w.mode(Mode::SoftwareShutdown).vsns_pd(false).isns_pd(false))?;
+
+        let dev = &mut self.dev;
+        dev.pb_cfg_1().write(|w| config.pb_cfg_1(w))?;
+        dev.misc_cfg_1()
+            .write(|w| config.protection.misc_cfg_1(w))?;
+
+        self.set_tdm(&config.tdm)?;
+
+        if let Some(limiter) = &config.limiter {
+            self.set_limiter(limiter)?;
+        }
+
+        if let Some(bop) = &config.bop {
+            self.set_bop(bop)?;
+        }
+
+        let dev = &mut self.dev;
+        let interrupts = &config.interrupts;
+        dev.int_clk_cfg().write(|w| interrupts.int_clk_cfg(w))?;
+        dev.misc().write(|w| interrupts.misc(w))?;
+
+        self.set_boost(&config.boost)?;
+        let dev = &mut self.dev;
+
+        let pdm = &config.pdm;
+        dev.pdm_config_0().write(|w| pdm.pdm_config_0(w))?;
+        dev.din_pd_pdm_config_3()
+            .modify(|w| pdm.din_pd_pdm_config_3(w))?;
+
+        for blob in config.tuning {
+            bulk::CommandIterator::new(blob).write_blocking(dev, delay)?;
+        }
+
+        dev.pwr_ctl().write(|w| {
+            w.mode(config.mode)
+                .vsns_pd(false)
+                .isns_pd(false)
+                .pdm_i_2_s_mode(pdm.i2s_mode)
+        })?;
+        self.mode = Some(config.mode);
+
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_tdm`].
+    pub fn set_tdm(&mut self, tdm: &TdmConfig) -> Result<(), Error<T::Error>> {
+        let dev = &mut self.dev;
+        dev.tdm_cfg_0().write(|w| tdm.tdm_cfg_0(w))?;
+        dev.tdm_cfg_1().write(|w| tdm.tdm_cfg_1(w))?;
+        dev.tdm_cfg_2().write(|w| tdm.tdm_cfg_2(w))?;
+        dev.tdm_cfg_3().write(|w| tdm.tdm_cfg_3(w))?;
+        dev.tdm_cfg_4().write(|w| tdm.tdm_cfg_4(w))?;
+        dev.tdm_cfg_5().write(|w| tdm.tdm_cfg_5(w))?;
+        dev.tdm_cfg_6().write(|w| tdm.tdm_cfg_6(w))?;
+        dev.tdm_cfg_7().write(|w| tdm.tdm_cfg_7(w))?;
+        dev.tdm_cfg_8().write(|w| tdm.tdm_cfg_8(w))?;
+        dev.tdm_cfg_9().write(|w| tdm.tdm_cfg_9(w))?;
+        dev.tdm_cfg_10().write(|w| tdm.tdm_cfg_10(w))?;
+        dev.clock_configuration()
+            .write(|w| tdm.clock_configuration(w))?;
+
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_asi2`].
+    pub fn set_asi2(&mut self, asi2: &Asi2Config) -> Result<(), Error<T::Error>> {
+        let dev = &mut self.dev;
+        dev.asi_2_config_0().write(|w| asi2.asi_2_config_0(w))?;
+        dev.asi_2_config_1().write(|w| asi2.asi_2_config_1(w))?;
+        dev.asi_2_config_2().write(|w| asi2.asi_2_config_2(w))?;
+        dev.asi_2_config_3().write(|w| asi2.asi_2_config_3(w))?;
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::asi2`].
+    pub fn asi2(&mut self) -> Result<Asi2Config, Error<T::Error>> {
+        Ok(Asi2Config::from_registers(
+            self.dev.asi_2_config_0().read()?,
+            self.dev.asi_2_config_1().read()?,
+            self.dev.asi_2_config_2().read()?,
+            self.dev.asi_2_config_3().read()?,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_clocking`].
+    pub fn set_clocking(&mut self, clocking: Clocking) -> Result<(), Error<T::Error>> {
+        self.dev.tdm_cfg_0().modify(|w| clocking.tdm_cfg_0(w))?;
+        self.dev
+            .clock_configuration()
+            .modify(|w| clocking.clock_configuration(w))?;
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::clocking`].
+    pub fn clocking(&mut self) -> Result<Clocking, Error<T::Error>> {
+        Ok(Clocking::from_registers(
+            self.dev.tdm_cfg_0().read()?,
+            self.dev.clock_configuration().read()?,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::detected_clocking`].
+    pub fn detected_clocking(&mut self) -> Result<DetectedClocking, Error<T::Error>> {
+        let dsp_mode_tdm_det = self.dev.dsp_mode_tdm_det().read()?;
+        Ok(DetectedClocking::from_register(dsp_mode_tdm_det))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_limiter`].
+    pub fn set_limiter(&mut self, limiter: &LimiterConfig) -> Result<(), Error<T::Error>> {
+        self.dev.lim_cfg_0().write(|w| limiter.lim_cfg_0(w))?;
+        self.dev.lim_cfg_1().write(|w| limiter.lim_cfg_1(w))?;
+        self.dev
+            .write_coefficients_blocking(LIMITER_ADDRESS, &limiter.to_coefficients())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::limiter`].
+    pub fn limiter(&mut self) -> Result<LimiterConfig, Error<T::Error>> {
+        let lim_cfg_0 = self.dev.lim_cfg_0().read()?;
+        let lim_cfg_1 = self.dev.lim_cfg_1().read()?;
+        let mut coefficients = [0; LIMITER_COEFFICIENTS];
+        self.dev
+            .read_coefficients_blocking(LIMITER_ADDRESS, &mut coefficients)?;
+        Ok(LimiterConfig::from_registers(
+            lim_cfg_0,
+            lim_cfg_1,
+            coefficients,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_bop`].
+    pub fn set_bop(&mut self, bop: &BopConfig) -> Result<(), Error<T::Error>> {
+        self.dev
+            .dsp_frequency_bop_cfg_0()
+            .write(|w| bop.dsp_frequency_bop_cfg_0(w))?;
+        self.dev.bop_cfg_0().write(|w| bop.bop_cfg_0(w))?;
+        if let Some(word) = bop.max_attenuation_coefficient() {
+            self.dev
+                .write_coefficient_blocking(MAX_ATTENUATION_ADDRESS, word)?;
+        }
+        self.dev
+            .write_coefficients_blocking(BOP_ADDRESS, &bop.to_coefficients())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::bop`].
+    pub fn bop(&mut self) -> Result<BopConfig, Error<T::Error>> {
+        let dsp_frequency_bop_cfg_0 = self.dev.dsp_frequency_bop_cfg_0().read()?;
+        let bop_cfg_0 = self.dev.bop_cfg_0().read()?;
+        let mut coefficients = [0; BOP_COEFFICIENTS];
+        self.dev
+            .read_coefficients_blocking(BOP_ADDRESS, &mut coefficients)?;
+        let max_attenuation = self
+            .dev
+            .read_coefficient_blocking(MAX_ATTENUATION_ADDRESS)?;
+        Ok(BopConfig::from_registers(
+            dsp_frequency_bop_cfg_0,
+            bop_cfg_0,
+            coefficients,
+            max_attenuation,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_boost`].
+    pub fn set_boost(&mut self, boost: &BoostConfig) -> Result<(), Error<T::Error>> {
+        self.dev.boost_cfg_1().write(|w| boost.boost_cfg_1(w))?;
+        self.dev.boost_cfg_2().write(|w| boost.boost_cfg_2(w))?;
+        self.dev.boost_cfg_3().write(|w| boost.boost_cfg_3(w))?;
+        self.dev
+            .bst_ilim_cfg_0()
+            .write(|w| boost.bst_ilim_cfg_0(w))?;
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::boost`].
+    pub fn boost(&mut self) -> Result<BoostConfig, Error<T::Error>> {
+        Ok(BoostConfig::from_registers(
+            self.dev.boost_cfg_1().read()?,
+            self.dev.boost_cfg_2().read()?,
+            self.dev.boost_cfg_3().read()?,
+            self.dev.bst_ilim_cfg_0().read()?,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_pdm`].
+    pub fn set_pdm(&mut self, pdm: &PdmConfig) -> Result<(), Error<T::Error>> {
+        self.dev.pdm_config_0().write(|w| pdm.pdm_config_0(w))?;
+        self.dev
+            .din_pd_pdm_config_3()
+            .modify(|w| pdm.din_pd_pdm_config_3(w))?;
+        self.dev
+            .pwr_ctl()
+            .modify(|w| w.pdm_i_2_s_mode(pdm.i2s_mode))?;
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::pdm`].
+    pub fn pdm(&mut self) -> Result<PdmConfig, Error<T::Error>> {
+        Ok(PdmConfig::from_registers(
+            self.dev.pdm_config_0().read()?,
+            self.dev.din_pd_pdm_config_3().read()?,
+            self.dev.pwr_ctl().read()?,
+        ))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::latched_faults`].
+    pub fn latched_faults(&mut self) -> Result<Faults, Error<T::Error>> {
+        Ok(Faults::from_registers([
+            self.dev.int_ltch_0().read()?.value(),
+            self.dev.int_ltch_1().read()?.value(),
+            self.dev.int_ltch_3().read()?.value(),
+            self.dev.int_ltch_4().read()?.value(),
+        ]))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::live_faults`].
+    pub fn live_faults(&mut self) -> Result<Faults, Error<T::Error>> {
+        Ok(Faults::from_registers([
+            self.dev.int_live_0().read()?.value(),
+            self.dev.int_live_1().read()?.value(),
+            self.dev.int_live_3().read()?.value(),
+            self.dev.int_live_4().read()?.value(),
+        ]))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_interrupt_mask`].
+    pub fn set_interrupt_mask(&mut self, masked: Faults) -> Result<(), Error<T::Error>> {
+        let [m0, m1, m2, m3] = masked.to_mask_registers();
+        self.dev.int_mask_0().write(|w| w.value(m0))?;
+        self.dev.int_mask_1().write(|w| w.value(m1))?;
+        self.dev.int_mask_2().write(|w| w.value(m2))?;
+        self.dev.int_mask_3().write(|w| w.value(m3))?;
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::clear_latched`].
+    pub fn clear_latched(&mut self) -> Result<(), Error<T::Error>> {
+        self.dev.int_clk_cfg().modify(|w| w.clr_intp_ltch(true))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_gain_db`].
+    pub fn set_gain_db(&mut self, dbv: f32) -> Result<AmpLevel, Error<T::Error>> {
+        let level = AmpLevel::from_dbv(dbv);
+        self.dev.pb_cfg_1().modify(|w| w.amp_level(level))?;
+        Ok(level)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::gain_db`].
+    pub fn gain_db(&mut self) -> Result<Option<f32>, Error<T::Error>> {
+        Ok(self.dev.pb_cfg_1().read()?.amp_level().to_dbv())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_volume_db`].
+    pub fn set_volume_db(&mut self, db: f32) -> Result<(), Error<T::Error>> {
+        self.dev
+            .write_coefficient_blocking(VOLUME_ADDRESS, volume_from_db(db).0)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::set_volume_linear`].
+    pub fn set_volume_linear(&mut self, gain: f32) -> Result<(), Error<T::Error>> {
+        self.dev
+            .write_coefficient_blocking(VOLUME_ADDRESS, volume_from_linear(gain).0)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::volume_db`].
+    pub fn volume_db(&mut self) -> Result<f32, Error<T::Error>> {
+        Ok(Q2_30(self.dev.read_coefficient_blocking(VOLUME_ADDRESS)?).to_db())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::ramp_volume_db`].
+    pub fn ramp_volume_db(
+        &mut self,
+        db: f32,
+        ramp: &VolumeRamp,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<T::Error>> {
+        let from = self.volume_db()?;
+        for step in ramp.steps(from, db) {
+            self.set_volume_db(step)?;
+            delay.delay_ms(ramp.interval_ms);
+        }
+        self.set_volume_db(db)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::load_diagnostics`].
+    pub fn load_diagnostics(
+        &mut self,
+        config: &LoadDiagnosticsConfig,
+        delay: &mut impl DelayNs,
+    ) -> Result<LoadDiagnostics, Error<T::Error>> {
+        let previous = match self.mode {
+            Some(mode) => mode,
+            None => self.refresh_mode()?,
+        };
+
+        self.dev.hold_sar_update().modify(|w| {
+            w.averaging_timer_init_ldg_mode_reg(config.averaging)
+                .switch_aux_scheme_ldg_reg(config.aux_clock)
+        })?;
+        self.clear_latched()?;
+
+        let result = self.measure_load(config, delay);
+
+        self.write_mode(previous)?;
+        self.clear_latched()?;
+
+        let (faults, ohms) = result?;
+        Ok(config.evaluate(faults, ohms))
+    }
+
+    fn measure_load(
+        &mut self,
+        config: &LoadDiagnosticsConfig,
+        delay: &mut impl DelayNs,
+    ) -> Result<(Faults, Option<f32>), Error<T::Error>> {
+        self.mode = None;
+        self.dev
+            .pwr_ctl()
+            .modify(|w| w.mode(Mode::LoadDiagnosticsActive))?;
+
+        let interval = config.poll_interval_ms.max(1);
+        let mut waited = 0;
+        let faults = loop {
+            delay.delay_ms(interval);
+            waited += interval;
+
+            let faults = self.latched_faults()?;
+            if faults
+                .intersects(Faults::LOAD_DIAGNOSTIC_DONE | Faults::OPEN_LOAD | Faults::SHORT_LOAD)
+            {
+                break faults;
+            }
+            if waited >= config.timeout_ms {
+                return Err(Error::Timeout);
+            }
+        };
+
+        let ohms = match config.impedance {
+            Some(address) => Some(diagnostics::impedance_ohms(
+                self.dev.read_coefficient_blocking(address)?,
+            )),
+            None => None,
+        };
+
+        Ok((faults, ohms))
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::amplification_level_or_mute`].
+    pub fn amplification_level_or_mute(
+        &mut self,
+        level: Option<AmpLevel>,
+    ) -> Result<(), Error<T::Error>> {
+        if let Some(level) = level {
+            if self.dev.pb_cfg_1().read()?.amp_level() != level {
+                self.dev.pb_cfg_1().modify(|w| w.amp_level(level))?;
+            }
+            self.write_mode(Mode::Active)
+        } else {
+            self.write_mode(Mode::Mute)
+        }
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::mute`].
+    pub fn mute(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(Mode::Mute, delay)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::unmute`].
+    pub fn unmute(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(Mode::Active, delay)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::unmute_when_locked`].
+    pub fn unmute_when_locked(
+        &mut self,
+        timeout_ms: u32,
+        delay: &mut impl DelayNs,
+    ) -> Result<ClockState, Error<T::Error>> {
+        let mut waited = 0;
+        let mut stable = 0;
+        loop {
+            let state = self.clock_state()?;
+            stable = if state.is_locked() { stable + 1 } else { 0 };
+            if stable >= CLOCK_STABLE_POLLS {
+                self.unmute(delay)?;
+                return Ok(state);
+            }
+            if waited >= timeout_ms {
+                return Err(Error::ClockTimeout(state));
+            }
+
+            delay.delay_ms(CLOCK_POLL_INTERVAL_MS);
+            waited += CLOCK_POLL_INTERVAL_MS;
+        }
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::clock_state`].
+    pub fn clock_state(&mut self) -> Result<ClockState, Error<T::Error>> {
+        Ok(ClockState {
+            detected: self.detected_clocking()?,
+            tdm_clock_error: self.dev.int_live_0().read()?.tdm_clock(),
+            pll_lock_error: self.dev.int_live_3().read()?.pll_lock(),
+        })
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::shutdown`].
+    pub fn shutdown(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        match self.mode {
+            Some(Mode::SoftwareShutdown) => return Ok(()),
+            Some(mode) => self.wake_mode = mode,
+            None => {}
+        }
+        self.set_mode(Mode::SoftwareShutdown, delay)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::wake`].
+    pub fn wake(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        self.set_mode(self.wake_mode, delay)
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::refresh_mode`].
+    pub fn refresh_mode(&mut self) -> Result<Mode, Error<T::Error>> {
+        let mode = self.dev.pwr_ctl().read()?.mode();
+        self.mode = Some(mode);
+        Ok(mode)
+    }
+
+    fn set_mode(&mut self, mode: Mode, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+        let Some(ramp) = self.mute_ramp.clone() else {
+            return self.write_mode(mode);
+        };
+
+        if self.mode == Some(mode) {
+            Ok(())
+        } else if mode == Mode::Active {
+            let volume = self.volume_db()?;
+            self.set_volume_db(f32::NEG_INFINITY)?;
+            self.write_mode(mode)?;
+            self.ramp_volume_db(volume, &ramp, delay)
+        } else if self.mode == Some(Mode::Active) {
+            let volume = self.volume_db()?;
+            self.ramp_volume_db(f32::NEG_INFINITY, &ramp, delay)?;
+            self.write_mode(mode)?;
+            self.set_volume_db(volume)
+        } else {
+            self.write_mode(mode)
+        }
+    }
+
+    pub(super) fn write_mode(&mut self, mode: Mode) -> Result<(), Error<T::Error>> {
+        if self.mode == Some(mode) {
+            return Ok(());
+        }
+
+        self.mode = None;
+        self.dev.pwr_ctl().modify(|w| w.mode(mode))?;
+        self.mode = Some(mode);
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`super::Tas2563::sar`].
+    pub fn sar(&mut self) -> Result<SarReading, Error<T::Error>> {
+        self.dev
+            .hold_sar_update()
+            .modify(|w| w.sar_data_hold_update(true))?;
+        let mut read = || {
+            Ok(SarReading {
+                pvdd: self.dev.pvdd().read()?.pvdd_cnv_dsp().into(),
+                vbat: self.dev.vbat().read()?.vbat_cnv().into(),
+                temperature: self.dev.temp().read()?.tmp_cnv().into(),
+            })
+        };
+        let reading = read();
+        // Release the hold regardless, lest the readback stays frozen.
+        self.dev
+            .hold_sar_update()
+            .modify(|w| w.sar_data_hold_update(false))?;
+        reading
+    }
+
+    pub fn adc(&mut self) -> Result<ADCReadout, Error<T::Error>> {
+        Ok(ADCReadout {
+            pvdd: self.dev.pvdd().read()?.pvdd_cnv_dsp(),
+            vbat: self.dev.vbat().read()?.vbat_cnv(),
+            temp: self.dev.temp().read()?.tmp_cnv(),
+        })
+    }
+}
@@ -0,0 +1,539 @@
+//! Boost converter in physical units.
+//!
+//! A [`BoostConfig`] can only be obtained through [`BoostConfigBuilder::build`], which picks the
+//! nearest encoding the device supports for each value, and rejects values outside of the range
+//! of the device. The getters of [`BoostConfig`] report the values actually programmed.
+
+use crate::ll::registers::{boost_cfg_1, boost_cfg_2, boost_cfg_3, bst_ilim_cfg_0};
+use crate::prelude::*;
+
+/// Regulation voltages in volts, with the lowest encoding being 1.
+const VOLTAGE_MIN_V: f32 = 6.0;
+const VOLTAGE_MAX_V: f32 = 12.5;
+const VOLTAGE_STEP_V: f32 = 0.5;
+
+/// Highest inductor value in microhenries.
+const INDUCTOR_MAX_UH: f32 = 2.5;
+
+/// Soft start current limits in amps, indexed by their encoding.
+const SOFT_START_LIMITS_A: [f32; 4] = [1.0, 1.5, 2.0, 2.5];
+
+/// PFM lower limits in kHz.
+const PFM_LOWER_LIMITS_KHZ: [(BstPfml, u16); 3] = [
+    (BstPfml::Frequency25Khz, 25),
+    (BstPfml::Frequency50Khz, 50),
+    (BstPfml::Frequency100Khz, 100),
+];
+
+/// Class-H step times in microseconds.
+const CLASS_H_STEP_TIMES_US: [(BstClassHStepTime, u16); 16] = [
+    (BstClassHStepTime::Step9Us, 9),
+    (BstClassHStepTime::Step18Us, 18),
+    (BstClassHStepTime::Step36Us, 36),
+    (BstClassHStepTime::Step54Us, 54),
+    (BstClassHStepTime::Step72Us, 72),
+    (BstClassHStepTime::Step90Us, 90),
+    (BstClassHStepTime::Step108Us, 108),
+    (BstClassHStepTime::Step135Us, 135),
+    (BstClassHStepTime::Step162Us, 162),
+    (BstClassHStepTime::Step198Us, 198),
+    (BstClassHStepTime::Step252Us, 252),
+    (BstClassHStepTime::Step342Us, 342),
+    (BstClassHStepTime::Step477Us, 477),
+    (BstClassHStepTime::Step612Us, 612),
+    (BstClassHStepTime::Step792Us, 792),
+    (BstClassHStepTime::Step990Us, 990),
+];
+
+/// Values the boost converter cannot be configured for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BoostError {
+    /// The regulation voltage is outside of 6V to 12.5V.
+    VoltageOutOfRange,
+    /// The inductor value is not positive or exceeds 2.5µH.
+    InductorOutOfRange,
+    /// The peak current limit is outside of 0.99A to 4A.
+    PeakCurrentOutOfRange,
+    /// The soft start current limit is outside of 1A to 2.5A.
+    SoftStartLimitOutOfRange,
+    /// The PFM lower limit is outside of 25kHz to 100kHz.
+    PfmLowerLimitOutOfRange,
+    /// The sync phase is neither 0 nor 180 degrees.
+    PhaseNotSupported,
+    /// The class-H step time is outside of 9µs to 990µs.
+    ClassHStepTimeOutOfRange,
+    /// The load regulation slope is reserved.
+    ReservedLoadRegulation,
+}
+
+impl core::fmt::Display for BoostError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BoostError::VoltageOutOfRange => write!(
+                f,
+                "boost voltage outside of {}V to {}V",
+                VOLTAGE_MIN_V, VOLTAGE_MAX_V
+            ),
+            BoostError::InductorOutOfRange => {
+                write!(f, "inductor outside of 0uH to {}uH", INDUCTOR_MAX_UH)
+            }
+            BoostError::PeakCurrentOutOfRange => write!(f, "peak current outside of 0.99A to 4A"),
+            BoostError::SoftStartLimitOutOfRange => {
+                write!(f, "soft start current limit outside of 1A to 2.5A")
+            }
+            BoostError::PfmLowerLimitOutOfRange => {
+                write!(f, "PFM lower limit outside of 25kHz to 100kHz")
+            }
+            BoostError::PhaseNotSupported => write!(f, "sync phase neither 0 nor 180 degrees"),
+            BoostError::ClassHStepTimeOutOfRange => {
+                write!(f, "class-H step time outside of 9us to 990us")
+            }
+            BoostError::ReservedLoadRegulation => write!(f, "reserved load regulation slope"),
+        }
+    }
+}
+
+impl core::error::Error for BoostError {}
+
+/// Validated boost converter configuration.
+///
+/// The default corresponds to the reset values of the device: class-H at 11V, a 0.6µH to 1.3µH
+/// inductor and a peak current limit of 3.96A.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoostConfig {
+    enable: bool,
+    mode: BstMode,
+    pfm_lower_limit: BstPfml,
+    dynamic_current_limit: bool,
+    voltage: BstVreg,
+    sync: bool,
+    phase: BstPa,
+    inductor: BstIr,
+    load_regulation: BstLr,
+    class_h_step_time: BstClassHStepTime,
+    peak_current: BoostPeakCurrentMaxRun,
+    soft_start_limit: u8,
+}
+
+impl Default for BoostConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            mode: BstMode::ClassH,
+            pfm_lower_limit: BstPfml::Frequency50Khz,
+            dynamic_current_limit: false,
+            voltage: BstVreg::Boost11V0,
+            sync: false,
+            phase: BstPa::Phase0Deg,
+            inductor: BstIr::Between0UH6And1UH3,
+            load_regulation: BstLr::LoadReg1V03APerV,
+            class_h_step_time: BstClassHStepTime::Step135Us,
+            peak_current: BoostPeakCurrentMaxRun(0x36),
+            soft_start_limit: 0x1,
+        }
+    }
+}
+
+impl BoostConfig {
+    /// Start a configuration from the defaults.
+    pub fn builder() -> BoostConfigBuilder {
+        Self::default().to_builder()
+    }
+
+    /// Continue from the values programmed by this configuration.
+    pub fn to_builder(&self) -> BoostConfigBuilder {
+        BoostConfigBuilder {
+            enable: self.enable,
+            mode: self.mode,
+            pfm_lower_limit_khz: self.pfm_lower_limit_khz(),
+            dynamic_current_limit: self.dynamic_current_limit,
+            voltage_v: self.voltage_v(),
+            sync: self.sync,
+            phase_deg: self.phase_deg(),
+            inductor_uh: match self.inductor {
+                BstIr::Less0UH6 => 0.5,
+                BstIr::Between1UH3And2UH5 => 2.2,
+                _ => 1.0,
+            },
+            load_regulation: self.load_regulation,
+            class_h_step_time_us: self.class_h_step_time_us(),
+            peak_current_a: self.peak_current_a(),
+            soft_start_limit_a: self.soft_start_limit_a(),
+        }
+    }
+
+    pub fn enable(&self) -> bool {
+        self.enable
+    }
+
+    pub fn mode(&self) -> BstMode {
+        self.mode
+    }
+
+    /// Lower limit of the switching frequency in kHz while in PFM, if any.
+    pub fn pfm_lower_limit_khz(&self) -> Option<u16> {
+        PFM_LOWER_LIMITS_KHZ
+            .iter()
+            .find(|(pfml, _)| *pfml == self.pfm_lower_limit)
+            .map(|(_, khz)| *khz)
+    }
+
+    pub fn dynamic_current_limit(&self) -> bool {
+        self.dynamic_current_limit
+    }
+
+    /// Regulation voltage in volts.
+    pub fn voltage_v(&self) -> f32 {
+        self.voltage.to_volts()
+    }
+
+    pub fn sync(&self) -> bool {
+        self.sync
+    }
+
+    /// Sync phase in degrees.
+    pub fn phase_deg(&self) -> u16 {
+        match self.phase {
+            BstPa::Phase0Deg => 0,
+            BstPa::Phase180Deg => 180,
+        }
+    }
+
+    /// Inductor range the converter is tuned for.
+    pub fn inductor(&self) -> BstIr {
+        self.inductor
+    }
+
+    pub fn load_regulation(&self) -> BstLr {
+        self.load_regulation
+    }
+
+    /// Class-H step time in microseconds.
+    pub fn class_h_step_time_us(&self) -> u16 {
+        CLASS_H_STEP_TIMES_US
+            .iter()
+            .find(|(time, _)| *time == self.class_h_step_time)
+            .map_or(0, |(_, us)| *us)
+    }
+
+    /// Peak current limit in amps.
+    pub fn peak_current_a(&self) -> f32 {
+        self.peak_current.to_milliamps() as f32 / 1000.0
+    }
+
+    /// Soft start current limit in amps.
+    pub fn soft_start_limit_a(&self) -> f32 {
+        SOFT_START_LIMITS_A[self.soft_start_limit as usize]
+    }
+
+    pub(crate) fn from_registers(
+        boost_cfg_1: boost_cfg_1::R,
+        boost_cfg_2: boost_cfg_2::R,
+        boost_cfg_3: boost_cfg_3::R,
+        bst_ilim_cfg_0: bst_ilim_cfg_0::R,
+    ) -> Self {
+        Self {
+            enable: boost_cfg_1.bst_en(),
+            mode: boost_cfg_1.bst_mode(),
+            pfm_lower_limit: boost_cfg_1.bst_pfml(),
+            dynamic_current_limit: boost_cfg_1.bst_dynamic_ilim_en(),
+            voltage: boost_cfg_2.bst_vreg(),
+            sync: boost_cfg_2.bst_sync(),
+            phase: boost_cfg_2.bst_pa(),
+            inductor: boost_cfg_2.bst_ir(),
+            load_regulation: boost_cfg_3.bst_lr(),
+            class_h_step_time: boost_cfg_3.bst_class_h_step_time(),
+            peak_current: bst_ilim_cfg_0.bst_ilim(),
+            soft_start_limit: bst_ilim_cfg_0.bst_ssl(),
+        }
+    }
+}
+
+/// Builder for [`BoostConfig`], see [`BoostConfig::builder`].
+#[derive(Debug, Clone)]
+pub struct BoostConfigBuilder {
+    enable: bool,
+    mode: BstMode,
+    pfm_lower_limit_khz: Option<u16>,
+    dynamic_current_limit: bool,
+    voltage_v: f32,
+    sync: bool,
+    phase_deg: u16,
+    inductor_uh: f32,
+    load_regulation: BstLr,
+    class_h_step_time_us: u16,
+    peak_current_a: f32,
+    soft_start_limit_a: f32,
+}
+
+impl BoostConfigBuilder {
+    /// Encode all values, rounding to the nearest supported value.
+    pub fn build(self) -> Result<BoostConfig, BoostError> {
+        if !(VOLTAGE_MIN_V..=VOLTAGE_MAX_V).contains(&self.voltage_v) {
+            return Err(BoostError::VoltageOutOfRange);
+        }
+        let steps = ((VOLTAGE_MAX_V - VOLTAGE_MIN_V) / VOLTAGE_STEP_V) as u8;
+        let voltage = nearest(
+            self.voltage_v,
+            (0..=steps).map(|i| {
+                (
+                    BstVreg::from(i + 1),
+                    VOLTAGE_MIN_V + i as f32 * VOLTAGE_STEP_V,
+                )
+            }),
+        );
+
+        let inductor = match self.inductor_uh {
+            uh if !(uh > 0.0 && uh <= INDUCTOR_MAX_UH) => {
+                return Err(BoostError::InductorOutOfRange)
+            }
+            uh if uh < 0.6 => BstIr::Less0UH6,
+            uh if uh < 1.3 => BstIr::Between0UH6And1UH3,
+            _ => BstIr::Between1UH3And2UH5,
+        };
+
+        if !(0.99..=4.0).contains(&self.peak_current_a) {
+            return Err(BoostError::PeakCurrentOutOfRange);
+        }
+        let peak_current = nearest(
+            self.peak_current_a,
+            (0..=0x37).map(|i| {
+                let code = BoostPeakCurrentMaxRun(i);
+                (code, code.to_milliamps() as f32 / 1000.0)
+            }),
+        );
+
+        let soft_start_range = SOFT_START_LIMITS_A[0]..=SOFT_START_LIMITS_A[3];
+        if !soft_start_range.contains(&self.soft_start_limit_a) {
+            return Err(BoostError::SoftStartLimitOutOfRange);
+        }
+        let soft_start_limit = nearest(self.soft_start_limit_a, (0..).zip(SOFT_START_LIMITS_A));
+
+        let pfm_lower_limit = match self.pfm_lower_limit_khz {
+            None => BstPfml::NoLowerLimit,
+            Some(khz) if !(25..=100).contains(&khz) => {
+                return Err(BoostError::PfmLowerLimitOutOfRange)
+            }
+            Some(khz) => nearest(
+                khz as f32,
+                PFM_LOWER_LIMITS_KHZ.map(|(pfml, khz)| (pfml, khz as f32)),
+            ),
+        };
+
+        let phase = match self.phase_deg % 360 {
+            0 => BstPa::Phase0Deg,
+            180 => BstPa::Phase180Deg,
+            _ => return Err(BoostError::PhaseNotSupported),
+        };
+
+        if !(9..=990).contains(&self.class_h_step_time_us) {
+            return Err(BoostError::ClassHStepTimeOutOfRange);
+        }
+        let class_h_step_time = nearest(
+            self.class_h_step_time_us as f32,
+            CLASS_H_STEP_TIMES_US.map(|(time, us)| (time, us as f32)),
+        );
+
+        if let BstLr::Reserved(_) = self.load_regulation {
+            return Err(BoostError::ReservedLoadRegulation);
+        }
+
+        Ok(BoostConfig {
+            enable: self.enable,
+            mode: self.mode,
+            pfm_lower_limit,
+            dynamic_current_limit: self.dynamic_current_limit,
+            voltage,
+            sync: self.sync,
+            phase,
+            inductor,
+            load_regulation: self.load_regulation,
+            class_h_step_time,
+            peak_current,
+            soft_start_limit,
+        })
+    }
+
+    pub fn enable(mut self, enable: bool) -> Self {
+        self.enable = enable;
+        self
+    }
+
+    /// Class-H, class-G (following the signal envelope), always on or off.
+    pub fn mode(mut self, mode: BstMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Lower limit of the switching frequency in kHz while in PFM, `None` for no limit.
+    pub fn pfm_lower_limit_khz(mut self, khz: Option<u16>) -> Self {
+        self.pfm_lower_limit_khz = khz;
+        self
+    }
+
+    /// Dynamic current limiter based on VBAT.
+    pub fn dynamic_current_limit(mut self, enable: bool) -> Self {
+        self.dynamic_current_limit = enable;
+        self
+    }
+
+    /// Regulation voltage in volts, in steps of 0.5V.
+    pub fn voltage_v(mut self, volts: f32) -> Self {
+        self.voltage_v = volts;
+        self
+    }
+
+    /// Synchronize the boost converter to the clock, with the given phase in degrees.
+    pub fn sync(mut self, sync: bool, phase_deg: u16) -> Self {
+        self.sync = sync;
+        self.phase_deg = phase_deg;
+        self
+    }
+
+    /// Value of the boost inductor in microhenries.
+    pub fn inductor_uh(mut self, microhenries: f32) -> Self {
+        self.inductor_uh = microhenries;
+        self
+    }
+
+    pub fn load_regulation(mut self, load_regulation: BstLr) -> Self {
+        self.load_regulation = load_regulation;
+        self
+    }
+
+    /// Class-H step time in microseconds.
+    pub fn class_h_step_time_us(mut self, microseconds: u16) -> Self {
+        self.class_h_step_time_us = microseconds;
+        self
+    }
+
+    /// Peak current limit in amps, in steps of 55mA.
+    pub fn peak_current_a(mut self, amps: f32) -> Self {
+        self.peak_current_a = amps;
+        self
+    }
+
+    /// Current limit in amps during soft start, in steps of 0.5A.
+    pub fn soft_start_limit_a(mut self, amps: f32) -> Self {
+        self.soft_start_limit_a = amps;
+        self
+    }
+}
+
+/// Encoding of the candidate closest to `value`, preferring the first on ties.
+fn nearest<T>(value: f32, candidates: impl IntoIterator<Item = (T, f32)>) -> T {
+    candidates
+        .into_iter()
+        .map(|(code, v)| (code, libm::fabsf(v - value)))
+        .reduce(|best, candidate| {
+            if candidate.1 < best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(code, _)| code)
+        .unwrap()
+}
+
+impl BoostConfig {
+    pub(crate) fn boost_cfg_1<'w>(&self, w: &'w mut boost_cfg_1::W) -> &'w mut boost_cfg_1::W {
+        w.bst_dynamic_ilim_en(self.dynamic_current_limit)
+            .bst_pfml(self.pfm_lower_limit)
+            .bst_en(self.enable)
+            .bst_mode(self.mode)
+    }
+
+    pub(crate) fn boost_cfg_2<'w>(&self, w: &'w mut boost_cfg_2::W) -> &'w mut boost_cfg_2::W {
+        w.bst_vreg(self.voltage)
+            .bst_pa(self.phase)
+            .bst_sync(self.sync)
+            .bst_ir(self.inductor)
+    }
+
+    pub(crate) fn boost_cfg_3<'w>(&self, w: &'w mut boost_cfg_3::W) -> &'w mut boost_cfg_3::W {
+        w.bst_lr(self.load_regulation)
+            .bst_class_h_step_time(self.class_h_step_time)
+    }
+
+    pub(crate) fn bst_ilim_cfg_0<'w>(
+        &self,
+        w: &'w mut bst_ilim_cfg_0::W,
+    ) -> &'w mut bst_ilim_cfg_0::W {
+        w.bst_ilim(self.peak_current).bst_ssl(self.soft_start_limit)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_roundtrip() {
+        assert_eq!(BoostConfig::builder().build(), Ok(BoostConfig::default()));
+    }
+
+    #[test]
+    fn nearest_encodings() {
+        let config = BoostConfig::builder()
+            .voltage_v(8.6)
+            .inductor_uh(1.5)
+            .peak_current_a(2.0)
+            .soft_start_limit_a(1.75)
+            .pfm_lower_limit_khz(Some(30))
+            .class_h_step_time_us(150)
+            .sync(true, 180)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.voltage_v(), 8.5);
+        assert_eq!(config.inductor(), BstIr::Between1UH3And2UH5);
+        assert_eq!(config.peak_current_a(), 1.98);
+        // Ties go to the lower value.
+        assert_eq!(config.soft_start_limit_a(), 1.5);
+        assert_eq!(config.pfm_lower_limit_khz(), Some(25));
+        assert_eq!(config.class_h_step_time_us(), 162);
+        assert_eq!(config.phase_deg(), 180);
+
+        let config = BoostConfig::builder().peak_current_a(4.0).build().unwrap();
+        assert_eq!(config.peak_current_a(), 4.0);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let builder = BoostConfig::builder;
+        assert_eq!(
+            builder().voltage_v(13.0).build(),
+            Err(BoostError::VoltageOutOfRange)
+        );
+        assert_eq!(
+            builder().voltage_v(f32::NAN).build(),
+            Err(BoostError::VoltageOutOfRange)
+        );
+        assert_eq!(
+            builder().inductor_uh(3.3).build(),
+            Err(BoostError::InductorOutOfRange)
+        );
+        assert_eq!(
+            builder().peak_current_a(0.5).build(),
+            Err(BoostError::PeakCurrentOutOfRange)
+        );
+        assert_eq!(
+            builder().pfm_lower_limit_khz(Some(200)).build(),
+            Err(BoostError::PfmLowerLimitOutOfRange)
+        );
+        assert_eq!(
+            builder().sync(true, 90).build(),
+            Err(BoostError::PhaseNotSupported)
+        );
+        assert_eq!(
+            builder().class_h_step_time_us(1000).build(),
+            Err(BoostError::ClassHStepTimeOutOfRange)
+        );
+        assert_eq!(
+            builder().load_regulation(BstLr::Reserved(0)).build(),
+            Err(BoostError::ReservedLoadRegulation)
+        );
+    }
+}
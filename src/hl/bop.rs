@@ -0,0 +1,151 @@
+//! Brown out prevention.
+//!
+//! Enabling and timing are configured through `dsp_frequency_bop_cfg0` and `bop_cfg0`, the
+//! thresholds are DSP coefficients in book 0 page 2, directly following those of the
+//! [limiter](super::limiter). The device has no separate maximum attenuation for brown out
+//! prevention, it is bounded by the maximum attenuation of the limiter instead.
+
+use super::limiter::{max_attenuation_from_coefficient, max_attenuation_to_coefficient};
+use crate::ll::coefficient::CoefficientAddress;
+use crate::ll::registers::{bop_cfg_0, dsp_frequency_bop_cfg_0};
+use crate::prelude::*;
+
+/// Location of the first brown out prevention coefficient, followed by the others in the order of
+/// [`BopConfig::to_coefficients`].
+pub const BOP_ADDRESS: CoefficientAddress = match CoefficientAddress::new(0x00, 0x02, 0x28) {
+    Some(address) => address,
+    None => panic!(),
+};
+
+/// Amount of brown out prevention coefficients.
+pub(crate) const BOP_COEFFICIENTS: usize = 2;
+
+/// Brown out prevention.
+///
+/// When VBAT drops below the threshold the playback is attenuated in steps, at the attack rate,
+/// up to the maximum attenuation shared with the limiter.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BopConfig {
+    pub enable: bool,
+    /// Mute the device on a brown out event.
+    pub mute: bool,
+    pub hold_time: BopHldTm,
+    pub attack_step: BopAtkSt,
+    pub attack_rate: BopAtkRt,
+    /// VBAT below which attenuation starts.
+    pub threshold: Millivolts,
+    /// Attenuation brown out prevention does not exceed, in dB.
+    ///
+    /// Written to the coefficient shared with
+    /// [`LimiterConfig::max_attenuation_db`](super::limiter::LimiterConfig::max_attenuation_db),
+    /// thus also applies to the limiter. `None` leaves it to the limiter configuration or tuning,
+    /// read back always reports the value in effect.
+    pub max_attenuation_db: Option<f32>,
+    /// Shut the device down when VBAT drops below this level, despite the attenuation.
+    pub shutdown_threshold: Option<Millivolts>,
+}
+
+impl Default for BopConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            mute: false,
+            hold_time: BopHldTm::Time500Ms,
+            attack_step: BopAtkSt::Step1DB0,
+            attack_rate: BopAtkRt::Step2Samples,
+            threshold: Millivolts(2900),
+            max_attenuation_db: None,
+            shutdown_threshold: None,
+        }
+    }
+}
+
+impl BopConfig {
+    /// Coefficients starting at [`BOP_ADDRESS`].
+    ///
+    /// The thresholds are stored in volts in 5.27 format. Without a shutdown threshold the last
+    /// coefficient is zero.
+    pub fn to_coefficients(&self) -> [i32; BOP_COEFFICIENTS] {
+        [
+            millivolts_to_q5_27(self.threshold).0,
+            self.shutdown_threshold
+                .map_or(Q5_27::ZERO, millivolts_to_q5_27)
+                .0,
+        ]
+    }
+
+    /// Coefficient at [`MAX_ATTENUATION_ADDRESS`](super::limiter::MAX_ATTENUATION_ADDRESS), if
+    /// any.
+    pub fn max_attenuation_coefficient(&self) -> Option<i32> {
+        self.max_attenuation_db.map(max_attenuation_to_coefficient)
+    }
+
+    pub(crate) fn from_registers(
+        dsp_frequency_bop_cfg_0: dsp_frequency_bop_cfg_0::R,
+        bop_cfg_0: bop_cfg_0::R,
+        coefficients: [i32; BOP_COEFFICIENTS],
+        max_attenuation: i32,
+    ) -> Self {
+        let [threshold, shutdown_threshold] = coefficients;
+        Self {
+            enable: dsp_frequency_bop_cfg_0.bop_en(),
+            mute: dsp_frequency_bop_cfg_0.bop_mute(),
+            hold_time: bop_cfg_0.bop_hld_tm(),
+            attack_step: bop_cfg_0.bop_atk_st(),
+            attack_rate: bop_cfg_0.bop_atk_rt(),
+            threshold: q5_27_to_millivolts(Q5_27(threshold)),
+            max_attenuation_db: Some(max_attenuation_from_coefficient(max_attenuation)),
+            shutdown_threshold: dsp_frequency_bop_cfg_0
+                .bosd_en()
+                .then(|| q5_27_to_millivolts(Q5_27(shutdown_threshold))),
+        }
+    }
+
+    pub(crate) fn dsp_frequency_bop_cfg_0<'w>(
+        &self,
+        w: &'w mut dsp_frequency_bop_cfg_0::W,
+    ) -> &'w mut dsp_frequency_bop_cfg_0::W {
+        w.bop_en(self.enable)
+            .bop_mute(self.mute)
+            .bosd_en(self.shutdown_threshold.is_some())
+    }
+
+    pub(crate) fn bop_cfg_0<'w>(&self, w: &'w mut bop_cfg_0::W) -> &'w mut bop_cfg_0::W {
+        w.bop_hld_tm(self.hold_time)
+            .bop_atk_st(self.attack_step)
+            .bop_atk_rt(self.attack_rate)
+    }
+}
+
+fn millivolts_to_q5_27(value: Millivolts) -> Q5_27 {
+    Q5_27::from_f32(value.0 as f32 / 1000.0)
+}
+
+fn q5_27_to_millivolts(value: Q5_27) -> Millivolts {
+    Millivolts(libm::roundf(value.to_f32() * 1000.0).clamp(0.0, u16::MAX as f32) as u16)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coefficients() {
+        let config = BopConfig {
+            shutdown_threshold: Some(Millivolts(2700)),
+            ..Default::default()
+        };
+        assert_eq!(config.to_coefficients(), [0x1733_3340, 0x1599_99a0]);
+
+        assert_eq!(BopConfig::default().to_coefficients()[1], 0);
+    }
+
+    #[test]
+    fn millivolts() {
+        assert_eq!(
+            q5_27_to_millivolts(millivolts_to_q5_27(Millivolts(3456))),
+            Millivolts(3456)
+        );
+        assert_eq!(q5_27_to_millivolts(Q5_27(-1)), Millivolts(0));
+    }
+}
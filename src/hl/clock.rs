@@ -0,0 +1,170 @@
+//! Clocking of the serial audio interface, either detected by the device or set manually.
+
+use super::tdm::TdmConfig;
+use crate::ll::registers::{clock_configuration, dsp_mode_tdm_det, tdm_cfg_0};
+use crate::prelude::*;
+
+/// How the device derives its clocks from the serial audio interface.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Clocking {
+    /// Detect the sample rate and the SBCLK to FSYNC ratio.
+    Auto,
+    /// Expect the given sample rate and SBCLK to FSYNC ratio.
+    ///
+    /// Reserved ratios are not supported by the device.
+    Manual {
+        sample_rate: SampRate,
+        sbclk_fs_ratio: SbclkFsRatio,
+    },
+}
+
+impl Clocking {
+    pub(crate) fn from_registers(
+        tdm_cfg_0: tdm_cfg_0::R,
+        clock_configuration: clock_configuration::R,
+    ) -> Self {
+        if clock_configuration.auto_clk() {
+            Clocking::Auto
+        } else {
+            Clocking::Manual {
+                sample_rate: tdm_cfg_0.samp_rate(),
+                sbclk_fs_ratio: clock_configuration.sbclk_fs_ratio(),
+            }
+        }
+    }
+
+    pub(crate) fn tdm_cfg_0<'w>(&self, w: &'w mut tdm_cfg_0::W) -> &'w mut tdm_cfg_0::W {
+        match *self {
+            Clocking::Auto => w.auto_rate(false),
+            Clocking::Manual { sample_rate, .. } => w.auto_rate(true).samp_rate(sample_rate),
+        }
+    }
+
+    pub(crate) fn clock_configuration<'w>(
+        &self,
+        w: &'w mut clock_configuration::W,
+    ) -> &'w mut clock_configuration::W {
+        match *self {
+            Clocking::Auto => w.auto_clk(true),
+            Clocking::Manual { sbclk_fs_ratio, .. } => {
+                w.auto_clk(false).sbclk_fs_ratio(sbclk_fs_ratio)
+            }
+        }
+    }
+}
+
+/// Sample rate and SBCLK to FSYNC ratio as detected by the device.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DetectedClocking {
+    /// Detected sample rate, [`FsRate::Error`] if none could be detected.
+    pub fs_rate: FsRate,
+    /// Detected ratio, [`FsRatio::Invalid`] if none could be detected.
+    pub fs_ratio: FsRatio,
+}
+
+impl DetectedClocking {
+    pub(crate) fn from_register(dsp_mode_tdm_det: dsp_mode_tdm_det::R) -> Self {
+        Self {
+            fs_rate: dsp_mode_tdm_det.fs_rate(),
+            fs_ratio: dsp_mode_tdm_det.fs_ratio(),
+        }
+    }
+
+    /// Detected sample rate, if any.
+    pub fn sample_rate(&self) -> Option<SampRate> {
+        Some(match self.fs_rate {
+            FsRate::Rate8Khz => SampRate::Rate8Khz,
+            FsRate::Rate16Khz => SampRate::Rate16Khz,
+            FsRate::Rate24Khz => SampRate::Rate24Khz,
+            FsRate::Rate32Khz => SampRate::Rate32Khz,
+            FsRate::Rate48Khz => SampRate::Rate48Khz,
+            FsRate::Rate96Khz => SampRate::Rate96Khz,
+            FsRate::Rate192Khz => SampRate::Rate192Khz,
+            FsRate::Error => return None,
+        })
+    }
+
+    /// Detected SBCLK to FSYNC ratio, if any.
+    pub fn sbclk_fs_ratio(&self) -> Option<SbclkFsRatio> {
+        Some(match self.fs_ratio {
+            FsRatio::Ratio16 => SbclkFsRatio::Ratio16,
+            FsRatio::Ratio24 => SbclkFsRatio::Ratio24,
+            FsRatio::Ratio32 => SbclkFsRatio::Ratio32,
+            FsRatio::Ratio48 => SbclkFsRatio::Ratio48,
+            FsRatio::Ratio64 => SbclkFsRatio::Ratio64,
+            FsRatio::Ratio96 => SbclkFsRatio::Ratio96,
+            FsRatio::Ratio128 => SbclkFsRatio::Ratio128,
+            FsRatio::Ratio192 => SbclkFsRatio::Ratio192,
+            FsRatio::Ratio256 => SbclkFsRatio::Ratio256,
+            FsRatio::Ratio384 => SbclkFsRatio::Ratio384,
+            FsRatio::Ratio512 => SbclkFsRatio::Ratio512,
+            FsRatio::Invalid | FsRatio::Reserved(_) => return None,
+        })
+    }
+
+    /// Whether both a sample rate and a ratio were detected.
+    pub fn is_valid(&self) -> bool {
+        self.sample_rate().is_some() && self.sbclk_fs_ratio().is_some()
+    }
+
+    /// Whether the detected clocking is the one the layout is designed for.
+    pub fn matches(&self, tdm: &TdmConfig) -> bool {
+        self.sample_rate() == Some(tdm.sample_rate())
+            && self.sbclk_fs_ratio() == Some(tdm.sbclk_fs_ratio())
+    }
+}
+
+/// Time between polls while waiting for the clocks, in milliseconds.
+pub(crate) const CLOCK_POLL_INTERVAL_MS: u32 = 1;
+/// Consecutive polls the clocks have to be fine for to be considered stable.
+pub(crate) const CLOCK_STABLE_POLLS: u8 = 2;
+
+/// Snapshot of the clock detection and the clock related interrupt sources.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockState {
+    pub detected: DetectedClocking,
+    /// The TDM clocks are missing or do not match the configuration.
+    pub tdm_clock_error: bool,
+    /// The PLL lost its lock.
+    pub pll_lock_error: bool,
+}
+
+impl ClockState {
+    /// Whether the clocks are detected and without errors.
+    pub fn is_locked(&self) -> bool {
+        self.detected.is_valid() && !self.tdm_clock_error && !self.pll_lock_error
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detected() {
+        let detected = DetectedClocking {
+            fs_rate: FsRate::Rate48Khz,
+            fs_ratio: FsRatio::Ratio64,
+        };
+        assert!(detected.is_valid());
+        assert!(detected.matches(&TdmConfig::default()));
+
+        let detected = DetectedClocking {
+            fs_rate: FsRate::Rate48Khz,
+            fs_ratio: FsRatio::Invalid,
+        };
+        assert!(!detected.is_valid());
+        assert_eq!(detected.sample_rate(), Some(SampRate::Rate48Khz));
+        assert!(!detected.matches(&TdmConfig::default()));
+
+        let detected = DetectedClocking {
+            fs_rate: FsRate::Error,
+            fs_ratio: FsRatio::Ratio256,
+        };
+        assert!(!detected.is_valid());
+        assert_eq!(detected.sbclk_fs_ratio(), Some(SbclkFsRatio::Ratio256));
+    }
+}
@@ -0,0 +1,127 @@
+//! Configuration of the TAS2563 as applied by [`Tas2563::init`](super::Tas2563::init).
+
+use super::{
+    boost::BoostConfig, bop::BopConfig, limiter::LimiterConfig, pdm::PdmConfig, tdm::TdmConfig,
+};
+use crate::ll::registers::{int_clk_cfg, misc, misc_cfg_1, pb_cfg_1};
+use crate::prelude::*;
+
+/// Complete device configuration, applied during power-up.
+///
+/// The defaults describe an I2S (TDM with 2 slots) bus at 48kHz with 24 bit samples in 32 bit
+/// slots, playing the left channel and returning current and voltage sense data, with all other
+/// blocks at their reset values or enabled with conservative settings. The limiter and brown out
+/// prevention coefficients are not written unless configured, such that they do not override a
+/// tuning.
+#[derive(Debug, Clone)]
+pub struct Tas2563Config<'a> {
+    /// Analog gain of the class-D amplifier.
+    pub amp_level: AmpLevel,
+    /// DC blocking filter on the playback path.
+    pub dc_blocker: bool,
+    pub protection: ProtectionConfig,
+    /// Serial audio interface and clocking.
+    pub tdm: TdmConfig,
+    /// VBAT tracking limiter, left to the reset values and tuning when `None`.
+    pub limiter: Option<LimiterConfig>,
+    /// Brown out prevention, left to the reset values and tuning when `None`.
+    pub bop: Option<BopConfig>,
+    pub interrupts: InterruptConfig,
+    pub boost: BoostConfig,
+    /// PDM microphone interface.
+    pub pdm: PdmConfig,
+    /// Bulk tuning blobs (see [`crate::bulk`]) applied in order after configuration.
+    ///
+    /// Blobs that also write book 0 page 0 override the configuration above, consider scrubbing those
+    /// registers from the blob.
+    pub tuning: &'a [&'a [u8]],
+    /// Mode to enter after initialization, typically [`Mode::Mute`] or [`Mode::Active`].
+    pub mode: Mode,
+}
+
+impl Default for Tas2563Config<'_> {
+    fn default() -> Self {
+        Self {
+            amp_level: AmpLevel::Amp16DBv0,
+            dc_blocker: true,
+            protection: Default::default(),
+            tdm: Default::default(),
+            limiter: None,
+            bop: None,
+            interrupts: Default::default(),
+            boost: Default::default(),
+            pdm: Default::default(),
+            tuning: &[],
+            mode: Mode::Mute,
+        }
+    }
+}
+
+/// Spread spectrum, pull-up and automatic retry behaviour.
+#[derive(Debug, Clone)]
+pub struct ProtectionConfig {
+    /// Low EMI spread spectrum of the class-D output.
+    pub spread_spectrum: bool,
+    /// Internal pull-up on the IRQZ pin.
+    pub irqz_pull_up: bool,
+    pub over_temperature_retry: bool,
+    pub over_current_retry: bool,
+    pub vbat_por_retry: bool,
+    pub cp_pg_retry: bool,
+}
+
+impl Default for ProtectionConfig {
+    fn default() -> Self {
+        Self {
+            spread_spectrum: true,
+            irqz_pull_up: false,
+            over_temperature_retry: true,
+            over_current_retry: true,
+            vbat_por_retry: true,
+            cp_pg_retry: true,
+        }
+    }
+}
+
+/// Behaviour of the IRQZ pin.
+#[derive(Debug, Clone)]
+pub struct InterruptConfig {
+    pub pin: IrqzPinCfg,
+    pub polarity: IrqzPol,
+}
+
+impl Default for InterruptConfig {
+    fn default() -> Self {
+        Self {
+            pin: IrqzPinCfg::UnmaskedLatched,
+            polarity: IrqzPol::ActiveLow,
+        }
+    }
+}
+
+impl Tas2563Config<'_> {
+    pub(crate) fn pb_cfg_1<'w>(&self, w: &'w mut pb_cfg_1::W) -> &'w mut pb_cfg_1::W {
+        w.amp_level(self.amp_level).dis_dc_blocker(!self.dc_blocker)
+    }
+}
+
+impl ProtectionConfig {
+    pub(crate) fn misc_cfg_1<'w>(&self, w: &'w mut misc_cfg_1::W) -> &'w mut misc_cfg_1::W {
+        w.amp_ss(self.spread_spectrum)
+            .irqz_pu(self.irqz_pull_up)
+            .ote_retry(self.over_temperature_retry)
+            .oce_retry(self.over_current_retry)
+            .vbat_por_retry(self.vbat_por_retry)
+            .cp_pg_retry(self.cp_pg_retry)
+    }
+}
+
+impl InterruptConfig {
+    pub(crate) fn int_clk_cfg<'w>(&self, w: &'w mut int_clk_cfg::W) -> &'w mut int_clk_cfg::W {
+        w.irqz_pin_cfg(self.pin)
+    }
+
+    pub(crate) fn misc<'w>(&self, w: &'w mut misc::W) -> &'w mut misc::W {
+        w.irqz_pol(self.polarity)
+    }
+}
@@ -0,0 +1,225 @@
+//! Lowering the gain or volume as the battery drains or the device heats up, before the device
+//! has to intervene through its limiter or brown out prevention.
+
+use super::{blocking, sar::SarReading, Tas2563};
+use crate::ll::{self, Tas2563Interface};
+use crate::prelude::*;
+use crate::Error;
+
+/// Which setting [`Derating`] limits.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DeratingTarget {
+    /// The analog gain in dBV, see [`Tas2563::set_gain_db`].
+    Gain,
+    /// The digital volume in dB, see [`Tas2563::set_volume_db`].
+    Volume,
+}
+
+/// Limit to apply under given conditions.
+///
+/// A rule applies when all of its conditions hold, a rule without conditions always applies.
+#[derive(Debug, Clone)]
+pub struct DeratingRule {
+    /// Applies when VBAT is below this level.
+    pub vbat_below: Option<Millivolts>,
+    /// Applies when the die temperature is above this level.
+    pub temperature_above: Option<Celsius>,
+    /// Highest gain or volume in dB(V) while the rule applies.
+    pub max_db: f32,
+}
+
+/// Margins by which a condition has to be cleared before a rule stops applying.
+#[derive(Debug, Clone)]
+pub struct DeratingHysteresis {
+    pub vbat: Millivolts,
+    pub temperature: Celsius,
+}
+
+impl Default for DeratingHysteresis {
+    fn default() -> Self {
+        Self {
+            vbat: Millivolts(100),
+            temperature: Celsius(5),
+        }
+    }
+}
+
+/// Periodically limits the gain or volume according to a table of [`DeratingRule`]s.
+///
+/// The lowest limit of all applying rules is used. At most 32 rules are supported, more fail to
+/// compile.
+#[derive(Debug, Clone)]
+pub struct Derating<'a, const N: usize> {
+    rules: &'a [DeratingRule; N],
+    target: DeratingTarget,
+    hysteresis: DeratingHysteresis,
+    /// Gain or volume the application asks for.
+    requested_db: f32,
+    /// Bit per rule that currently applies.
+    active: u32,
+    /// Gain or volume last written.
+    applied_db: Option<f32>,
+}
+
+impl<'a, const N: usize> Derating<'a, N> {
+    /// Set up derating of `target`, which the application would otherwise set to `requested_db`.
+    pub fn new(
+        rules: &'a [DeratingRule; N],
+        target: DeratingTarget,
+        hysteresis: DeratingHysteresis,
+        requested_db: f32,
+    ) -> Self {
+        const { assert!(N <= 32, "Derating supports at most 32 rules") };
+        Self {
+            rules,
+            target,
+            hysteresis,
+            requested_db,
+            active: 0,
+            applied_db: None,
+        }
+    }
+
+    /// Change the gain or volume the application asks for, applied on the next tick.
+    pub fn set_requested_db(&mut self, db: f32) {
+        self.requested_db = db;
+    }
+
+    /// Lowest limit of the rules that applied during the last tick.
+    pub fn limit_db(&self) -> Option<f32> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.active & (1 << i) != 0)
+            .map(|(_, rule)| rule.max_db)
+            .reduce(f32::min)
+    }
+
+    /// Measure VBAT and temperature, and write the gain or volume if it changed.
+    ///
+    /// Returns the gain or volume in effect.
+    pub async fn tick<T: Tas2563Interface>(
+        &mut self,
+        amp: &mut Tas2563<T>,
+    ) -> Result<f32, Error<T::Error>> {
+        let reading = amp.sar().await?;
+        let db = self.evaluate(&reading);
+
+        if self.applied_db != Some(db) {
+            self.applied_db = None;
+            match self.target {
+                DeratingTarget::Gain => {
+                    amp.set_gain_db(db).await?;
+                }
+                DeratingTarget::Volume => amp.set_volume_db(db).await?,
+            }
+            self.applied_db = Some(db);
+        }
+
+        Ok(db)
+    }
+
+    /// Blocking counterpart of [`Derating::tick`].
+    pub fn tick_blocking<T: ll::blocking::Tas2563Interface>(
+        &mut self,
+        amp: &mut blocking::Tas2563<T>,
+    ) -> Result<f32, Error<T::Error>> {
+        let reading = amp.sar()?;
+        let db = self.evaluate(&reading);
+
+        if self.applied_db != Some(db) {
+            self.applied_db = None;
+            match self.target {
+                DeratingTarget::Gain => {
+                    amp.set_gain_db(db)?;
+                }
+                DeratingTarget::Volume => amp.set_volume_db(db)?,
+            }
+            self.applied_db = Some(db);
+        }
+
+        Ok(db)
+    }
+
+    /// Update which rules apply, and return the resulting gain or volume.
+    fn evaluate(&mut self, reading: &SarReading) -> f32 {
+        for (i, rule) in self.rules.iter().enumerate() {
+            let bit = 1 << i;
+            // Rules that apply keep doing so until their conditions are cleared by the margin.
+            let (vbat_margin, temperature_margin) = if self.active & bit != 0 {
+                (self.hysteresis.vbat.0, self.hysteresis.temperature.0)
+            } else {
+                (0, 0)
+            };
+
+            let vbat = rule
+                .vbat_below
+                .is_none_or(|v| reading.vbat.0 < v.0.saturating_add(vbat_margin));
+            let temperature = rule
+                .temperature_above
+                .is_none_or(|t| reading.temperature.0 > t.0.saturating_sub(temperature_margin));
+
+            if vbat && temperature {
+                self.active |= bit;
+            } else {
+                self.active &= !bit;
+            }
+        }
+
+        match self.limit_db() {
+            Some(limit) => self.requested_db.min(limit),
+            None => self.requested_db,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reading(vbat: u16, temperature: i16) -> SarReading {
+        SarReading {
+            pvdd: Millivolts(vbat),
+            vbat: Millivolts(vbat),
+            temperature: Celsius(temperature),
+        }
+    }
+
+    #[test]
+    fn evaluate() {
+        let rules = [
+            DeratingRule {
+                vbat_below: Some(Millivolts(3500)),
+                temperature_above: None,
+                max_db: -6.0,
+            },
+            DeratingRule {
+                vbat_below: Some(Millivolts(3300)),
+                temperature_above: Some(Celsius(60)),
+                max_db: -12.0,
+            },
+        ];
+        let mut derating = Derating::new(
+            &rules,
+            DeratingTarget::Volume,
+            DeratingHysteresis::default(),
+            -3.0,
+        );
+
+        assert_eq!(derating.evaluate(&reading(3700, 25)), -3.0);
+        assert_eq!(derating.limit_db(), None);
+        assert_eq!(derating.evaluate(&reading(3450, 25)), -6.0);
+        // Only both a low battery and a high temperature apply the second rule.
+        assert_eq!(derating.evaluate(&reading(3250, 25)), -6.0);
+        assert_eq!(derating.evaluate(&reading(3250, 70)), -12.0);
+
+        // Recovering within the hysteresis margins does not lift the limits.
+        assert_eq!(derating.evaluate(&reading(3350, 58)), -12.0);
+        assert_eq!(derating.evaluate(&reading(3550, 50)), -6.0);
+        assert_eq!(derating.evaluate(&reading(3600, 50)), -3.0);
+
+        derating.set_requested_db(-20.0);
+        assert_eq!(derating.evaluate(&reading(3450, 25)), -20.0);
+    }
+}
@@ -0,0 +1,109 @@
+//! Load diagnostics, detecting open and shorted speakers.
+
+use super::faults::Faults;
+use crate::ll::coefficient::CoefficientAddress;
+use crate::prelude::*;
+
+/// How to run [`Tas2563::load_diagnostics`](super::Tas2563::load_diagnostics).
+#[derive(Debug, Clone)]
+pub struct LoadDiagnosticsConfig {
+    /// Duration of the averaging of the V/I sense data, the raw value of
+    /// `hold_sar_update.averaging_timer_init_ldg_mode_reg`.
+    pub averaging: u8,
+    /// Use the auxiliary clock, such that diagnostics run without clocks on the TDM bus.
+    pub aux_clock: bool,
+    /// Location of the measured impedance in 5.27 format ohms, as exposed by the tuning in use.
+    ///
+    /// Without it only open and shorted loads are detected.
+    pub impedance: Option<CoefficientAddress>,
+    /// Range of acceptable impedances in ohms.
+    pub impedance_range: (f32, f32),
+    /// Time between polls for completion in milliseconds.
+    pub poll_interval_ms: u32,
+    /// Time after which to give up in milliseconds.
+    pub timeout_ms: u32,
+}
+
+impl Default for LoadDiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            averaging: 0,
+            aux_clock: true,
+            impedance: None,
+            impedance_range: (4.0, 16.0),
+            poll_interval_ms: 10,
+            timeout_ms: 500,
+        }
+    }
+}
+
+/// Outcome of load diagnostics.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LoadDiagnostics {
+    OpenLoad,
+    ShortedLoad,
+    /// The measured impedance in ohms is outside of the configured range.
+    OutOfRange {
+        ohms: f32,
+    },
+    /// The load is connected, with the measured impedance in ohms if configured.
+    Ok {
+        ohms: Option<f32>,
+    },
+}
+
+impl LoadDiagnosticsConfig {
+    pub(crate) fn evaluate(&self, faults: Faults, ohms: Option<f32>) -> LoadDiagnostics {
+        if faults.contains(Faults::OPEN_LOAD) {
+            return LoadDiagnostics::OpenLoad;
+        }
+        if faults.contains(Faults::SHORT_LOAD) {
+            return LoadDiagnostics::ShortedLoad;
+        }
+
+        let (min, max) = self.impedance_range;
+        match ohms {
+            Some(ohms) if !(min..=max).contains(&ohms) => LoadDiagnostics::OutOfRange { ohms },
+            ohms => LoadDiagnostics::Ok { ohms },
+        }
+    }
+}
+
+/// Impedance in ohms from its coefficient.
+pub(crate) fn impedance_ohms(word: i32) -> f32 {
+    Q5_27(word).to_f32()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn evaluate() {
+        let config = LoadDiagnosticsConfig::default();
+        let done = Faults::LOAD_DIAGNOSTIC_DONE;
+
+        assert_eq!(
+            config.evaluate(done | Faults::OPEN_LOAD, Some(100.0)),
+            LoadDiagnostics::OpenLoad
+        );
+        assert_eq!(
+            config.evaluate(done | Faults::SHORT_LOAD, None),
+            LoadDiagnostics::ShortedLoad
+        );
+        assert_eq!(
+            config.evaluate(done, Some(2.5)),
+            LoadDiagnostics::OutOfRange { ohms: 2.5 }
+        );
+        assert_eq!(
+            config.evaluate(done, Some(8.0)),
+            LoadDiagnostics::Ok { ohms: Some(8.0) }
+        );
+        assert_eq!(
+            config.evaluate(done, None),
+            LoadDiagnostics::Ok { ohms: None }
+        );
+        assert_eq!(impedance_ohms(8 << 27), 8.0);
+    }
+}
@@ -0,0 +1,179 @@
+//! Interrupt sources of the device, as found in the interrupt mask, live and latched registers.
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub};
+
+/// Set of interrupt sources.
+///
+/// Each of the four interrupt register groups (mask 0-3, live and latched 0/1/3/4) occupies one
+/// byte, in the same bit positions as the device uses.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Faults(u32);
+
+impl Faults {
+    pub const OVER_TEMPERATURE: Self = Self(1 << 0);
+    pub const OVER_CURRENT: Self = Self(1 << 1);
+    pub const TDM_CLOCK_ERROR: Self = Self(1 << 2);
+    pub const LIMITER_ACTIVE: Self = Self(1 << 3);
+    /// VBAT dropped below the limiter inflection point.
+    pub const VBAT_BELOW_LIMITER: Self = Self(1 << 4);
+    pub const LIMITER_MAX_ATTENUATION: Self = Self(1 << 5);
+    pub const LIMITER_INFINITE_HOLD: Self = Self(1 << 6);
+    pub const LIMITER_MUTE: Self = Self(1 << 7);
+
+    pub const VBAT_BROWNOUT: Self = Self(1 << 8);
+    pub const BROWNOUT_PROTECTION_ACTIVE: Self = Self(1 << 9);
+    pub const BROWNOUT_POWER_DOWN: Self = Self(1 << 10);
+    pub const OPEN_LOAD: Self = Self(1 << 11);
+    pub const SHORT_LOAD: Self = Self(1 << 12);
+    pub const LOAD_DIAGNOSTIC_DONE: Self = Self(1 << 13);
+
+    pub const POWER_UP: Self = Self(1 << 16);
+    /// Charge pump power good fault.
+    pub const CHARGE_PUMP: Self = Self(1 << 17);
+    pub const BOOST_OV_CLAMP: Self = Self(1 << 18);
+    pub const DC_DETECT: Self = Self(1 << 19);
+    pub const PLL_LOCK: Self = Self(1 << 20);
+    pub const VBAT_POR: Self = Self(1 << 21);
+    pub const BOOST_CLOCK_ERROR: Self = Self(1 << 22);
+    pub const DAC_MOD_CLOCK_ERROR: Self = Self(1 << 23);
+
+    pub const ASI2_CLOCK_ERROR: Self = Self(1 << 27);
+    pub const PDM_CLOCK_ERROR: Self = Self(1 << 28);
+    pub const POWER_DOWN: Self = Self(1 << 31);
+
+    /// Bits of each register that carry an interrupt source.
+    const KNOWN: [u8; 4] = [0xff, 0x3f, 0xff, 0x98];
+    /// Reset values of the mask registers, used for the bits that are not an interrupt source.
+    const MASK_RESET: [u8; 4] = [0xfc, 0xa6, 0xdf, 0xff];
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn all() -> Self {
+        Self(u32::from_le_bytes(Self::KNOWN))
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Construct from raw bits, dropping those that are not an interrupt source.
+    pub const fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::all().0)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn intersects(&self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Construct from the values of the live or latched registers 0, 1, 3 and 4.
+    pub(crate) fn from_registers(registers: [u8; 4]) -> Self {
+        Self::from_bits_truncate(u32::from_le_bytes(registers))
+    }
+
+    /// Values of the mask registers 0 through 3 masking these sources.
+    pub(crate) fn to_mask_registers(self) -> [u8; 4] {
+        let mut registers = self.0.to_le_bytes();
+        for (r, (known, reset)) in registers
+            .iter_mut()
+            .zip(Self::KNOWN.iter().zip(Self::MASK_RESET))
+        {
+            *r = (*r & known) | (reset & !known);
+        }
+        registers
+    }
+}
+
+impl BitOr for Faults {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Faults {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Faults {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for Faults {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl Sub for Faults {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl Not for Faults {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self::from_bits_truncate(!self.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Faults;
+
+    #[test]
+    fn registers() {
+        assert_eq!(
+            Faults::from_registers([0x00, 0xc0, 0x00, 0x67]),
+            Faults::empty()
+        );
+        assert_eq!(
+            Faults::from_registers([0x03, 0x08, 0x04, 0x10]),
+            Faults::OVER_TEMPERATURE
+                | Faults::OVER_CURRENT
+                | Faults::OPEN_LOAD
+                | Faults::BOOST_OV_CLAMP
+                | Faults::PDM_CLOCK_ERROR
+        );
+
+        // Reserved bits keep their reset values.
+        assert_eq!(
+            Faults::empty().to_mask_registers(),
+            [0x00, 0x80, 0x00, 0x67]
+        );
+        assert_eq!(Faults::all().to_mask_registers(), [0xff, 0xbf, 0xff, 0xff]);
+        assert_eq!(
+            (!(Faults::OVER_TEMPERATURE | Faults::OVER_CURRENT)).to_mask_registers(),
+            [0xfc, 0xbf, 0xff, 0xff]
+        );
+    }
+}
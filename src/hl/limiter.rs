@@ -0,0 +1,148 @@
+//! VBAT tracking limiter.
+//!
+//! Timing is configured through `lim_cfg0` and `lim_cfg1`, the thresholds and attenuation are DSP
+//! coefficients in book 0 page 2, as written by PPC3 tuning programs from register 0x14 on.
+
+use crate::ll::coefficient::CoefficientAddress;
+use crate::ll::registers::{lim_cfg_0, lim_cfg_1};
+use crate::prelude::*;
+
+/// Location of the first limiter coefficient, followed by the others in the order of
+/// [`LimiterConfig::to_coefficients`].
+pub const LIMITER_ADDRESS: CoefficientAddress = match CoefficientAddress::new(0x00, 0x02, 0x14) {
+    Some(address) => address,
+    None => panic!(),
+};
+
+/// Location of the maximum attenuation, the first limiter coefficient, which brown out prevention
+/// shares with the limiter.
+pub const MAX_ATTENUATION_ADDRESS: CoefficientAddress = LIMITER_ADDRESS;
+
+/// Amount of limiter coefficients.
+pub(crate) const LIMITER_COEFFICIENTS: usize = 5;
+
+/// VBAT tracking limiter.
+///
+/// The threshold tracks VBAT below the inflection point with the given slope, between the minimum
+/// and maximum threshold.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LimiterConfig {
+    pub enable: bool,
+    pub attack_rate: LimbAtkRt,
+    pub attack_step: LimbAtkSt,
+    pub hold_time: LimbHldTm,
+    pub release_rate: LimbRlsRt,
+    pub release_step: LimbRlsSt,
+    pub threshold_source: VbatLimThSelection,
+    /// Attenuation the limiter does not exceed, in dB.
+    ///
+    /// Also limits the attenuation by brown out prevention, see [`BopConfig`](super::bop::BopConfig).
+    pub max_attenuation_db: f32,
+    /// Threshold in volts peak while VBAT is above the inflection point.
+    pub threshold_max_v: f32,
+    /// Lowest threshold in volts peak.
+    pub threshold_min_v: f32,
+    /// VBAT in volts below which the threshold tracks VBAT.
+    pub inflection_point_v: f32,
+    /// Volts of threshold per volt of VBAT below the inflection point.
+    pub slope: f32,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            attack_rate: LimbAtkRt::Step2Samples,
+            attack_step: LimbAtkSt::Step0DB5,
+            hold_time: LimbHldTm::Time500Ms,
+            release_rate: LimbRlsRt::Step640Ms,
+            release_step: LimbRlsSt::Step0DB5,
+            threshold_source: VbatLimThSelection::User,
+            max_attenuation_db: 9.0,
+            threshold_max_v: 9.0,
+            threshold_min_v: 4.0,
+            inflection_point_v: 3.5,
+            slope: 1.0,
+        }
+    }
+}
+
+impl LimiterConfig {
+    /// Coefficients starting at [`LIMITER_ADDRESS`].
+    ///
+    /// The maximum attenuation is stored as linear gain in 1.31 format, the thresholds, inflection
+    /// point and slope in 5.27 format.
+    pub fn to_coefficients(&self) -> [i32; LIMITER_COEFFICIENTS] {
+        [
+            max_attenuation_to_coefficient(self.max_attenuation_db),
+            Q5_27::from_f32(self.threshold_max_v).0,
+            Q5_27::from_f32(self.threshold_min_v).0,
+            Q5_27::from_f32(self.inflection_point_v).0,
+            Q5_27::from_f32(self.slope).0,
+        ]
+    }
+
+    pub(crate) fn from_registers(
+        lim_cfg_0: lim_cfg_0::R,
+        lim_cfg_1: lim_cfg_1::R,
+        coefficients: [i32; LIMITER_COEFFICIENTS],
+    ) -> Self {
+        let [attenuation, threshold_max, threshold_min, inflection_point, slope] = coefficients;
+        Self {
+            enable: lim_cfg_0.limb_en(),
+            attack_rate: lim_cfg_0.limb_atk_rt(),
+            attack_step: lim_cfg_0.limb_atk_st(),
+            threshold_source: lim_cfg_0.vbat_lim_th_selection(),
+            hold_time: lim_cfg_1.limb_hld_tm(),
+            release_rate: lim_cfg_1.limb_rls_rt(),
+            release_step: lim_cfg_1.limb_rls_st(),
+            max_attenuation_db: max_attenuation_from_coefficient(attenuation),
+            threshold_max_v: Q5_27(threshold_max).to_f32(),
+            threshold_min_v: Q5_27(threshold_min).to_f32(),
+            inflection_point_v: Q5_27(inflection_point).to_f32(),
+            slope: Q5_27(slope).to_f32(),
+        }
+    }
+
+    pub(crate) fn lim_cfg_0<'w>(&self, w: &'w mut lim_cfg_0::W) -> &'w mut lim_cfg_0::W {
+        w.limb_en(self.enable)
+            .limb_atk_rt(self.attack_rate)
+            .limb_atk_st(self.attack_step)
+            .vbat_lim_th_selection(self.threshold_source)
+    }
+
+    pub(crate) fn lim_cfg_1<'w>(&self, w: &'w mut lim_cfg_1::W) -> &'w mut lim_cfg_1::W {
+        w.limb_hld_tm(self.hold_time)
+            .limb_rls_rt(self.release_rate)
+            .limb_rls_st(self.release_step)
+    }
+}
+
+/// Maximum attenuation in dB as linear gain in 1.31 format, the sign of `db` is ignored.
+pub(crate) fn max_attenuation_to_coefficient(db: f32) -> i32 {
+    Q1_31::from_db(-libm::fabsf(db)).0
+}
+
+pub(crate) fn max_attenuation_from_coefficient(word: i32) -> f32 {
+    -Q1_31(word).to_db()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coefficients() {
+        let config = LimiterConfig::default();
+        assert_eq!(
+            config.to_coefficients(),
+            [
+                0x2d6a_8680,
+                0x4800_0000,
+                0x2000_0000,
+                0x1c00_0000,
+                0x0800_0000
+            ]
+        );
+    }
+}
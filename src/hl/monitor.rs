@@ -0,0 +1,79 @@
+//! Fault monitoring driven by the IRQZ pin.
+
+use embedded_hal::digital::Error as _;
+use embedded_hal_async::digital::Wait;
+
+use super::{faults::Faults, Tas2563};
+use crate::ll::Tas2563Interface;
+use crate::prelude::*;
+use crate::Error;
+
+/// Waits on the IRQZ pin and reports the faults that asserted it.
+///
+/// Intended to be owned by a single task per amplifier, other operations on the amplifier can be
+/// performed in between faults through [`FaultMonitor::amp`].
+pub struct FaultMonitor<T, P> {
+    amp: Tas2563<T>,
+    irqz: P,
+    polarity: IrqzPol,
+}
+
+impl<T, P> FaultMonitor<T, P>
+where
+    T: Tas2563Interface,
+    P: Wait,
+{
+    /// Configure IRQZ as latched with the given polarity and clear any faults latched before.
+    ///
+    /// The polarity should match how the pin is wired, e.g. [`IrqzPol::ActiveLow`] with a pull-up.
+    pub async fn new(
+        mut amp: Tas2563<T>,
+        irqz: P,
+        polarity: IrqzPol,
+    ) -> Result<Self, Error<T::Error>> {
+        amp.dev
+            .int_clk_cfg()
+            .modify_async(|w| w.irqz_pin_cfg(IrqzPinCfg::UnmaskedLatched))
+            .await?;
+        amp.dev
+            .misc()
+            .modify_async(|w| w.irqz_pol(polarity))
+            .await?;
+        amp.clear_latched().await?;
+
+        Ok(Self {
+            amp,
+            irqz,
+            polarity,
+        })
+    }
+
+    /// Get access to the monitored amplifier.
+    pub fn amp(&mut self) -> &mut Tas2563<T> {
+        &mut self.amp
+    }
+
+    /// Return the amplifier and the IRQZ pin.
+    pub fn release(self) -> (Tas2563<T>, P) {
+        (self.amp, self.irqz)
+    }
+
+    /// Wait until IRQZ is asserted, then read and clear the latched faults.
+    ///
+    /// Faults that persist are latched again right away, and will thus be reported by the next
+    /// call as well. Faults masked with [`Tas2563::set_interrupt_mask`] do not assert IRQZ, but
+    /// are reported when latched alongside others.
+    pub async fn next_fault(&mut self) -> Result<Faults, Error<T::Error>> {
+        // Waiting for the level rather than an edge ensures that an IRQZ that was already asserted
+        // is not missed.
+        match self.polarity {
+            IrqzPol::ActiveHigh => self.irqz.wait_for_high().await,
+            IrqzPol::ActiveLow => self.irqz.wait_for_low().await,
+        }
+        .map_err(|e| Error::Pin(e.kind()))?;
+
+        let faults = self.amp.latched_faults().await?;
+        self.amp.clear_latched().await?;
+        Ok(faults)
+    }
+}
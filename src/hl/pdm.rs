@@ -0,0 +1,95 @@
+//! PDM microphone interface, on the PDMCK and PDMD pins (PAD0).
+//!
+//! A missing PDM clock is reported through [`Faults::PDM_CLOCK_ERROR`](super::faults::Faults),
+//! both in the latched and live faults and, when unmasked, on IRQZ.
+//!
+//! The register map has no TDM TX slot fields for the microphones, unlike for the sense data in
+//! [`TdmConfig`](super::tdm::TdmConfig), so the microphone data can not be routed to TDM TX slots.
+
+use crate::ll::registers::{din_pd_pdm_config_3, pdm_config_0, pwr_ctl};
+use crate::prelude::*;
+
+/// PDM microphone interface, disabled by default.
+///
+/// Two microphones can share PDMD, one captured on each clock edge.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PdmConfig {
+    /// Drive PDMCK, or receive it from elsewhere.
+    pub clock_mode: PdmMicSlv,
+    pub mic1: bool,
+    pub mic2: bool,
+    /// Clock edges on which each microphone is captured.
+    pub capture_edge: PdmPad0CapEdge,
+    pub rate: PdmRatePad0,
+    /// Detect a missing PDM clock.
+    pub clock_error_detection: bool,
+    /// Gate PDMCK while driving it.
+    pub clock_gating: bool,
+    /// Weak pull down on PDMCK.
+    pub clock_pull_down: bool,
+    /// Weak pull down on PDMD.
+    pub data_pull_down: bool,
+    /// Transmit the microphone data in I2S mode rather than in TDM mode.
+    pub i2s_mode: bool,
+}
+
+impl Default for PdmConfig {
+    fn default() -> Self {
+        Self {
+            clock_mode: PdmMicSlv::MasterMode,
+            mic1: false,
+            mic2: false,
+            capture_edge: PdmPad0CapEdge::Mic1PositiveEdgeMic2NegativeEdge,
+            rate: PdmRatePad0::Rate3Mhz072,
+            clock_error_detection: true,
+            clock_gating: false,
+            clock_pull_down: false,
+            data_pull_down: false,
+            i2s_mode: false,
+        }
+    }
+}
+
+impl PdmConfig {
+    /// Whether any microphone is enabled.
+    pub fn enabled(&self) -> bool {
+        self.mic1 || self.mic2
+    }
+
+    pub(crate) fn from_registers(
+        pdm_config_0: pdm_config_0::R,
+        din_pd_pdm_config_3: din_pd_pdm_config_3::R,
+        pwr_ctl: pwr_ctl::R,
+    ) -> Self {
+        Self {
+            clock_mode: pdm_config_0.pdm_mic_slv(),
+            mic1: pdm_config_0.pdm_mic_1_en(),
+            mic2: pdm_config_0.pdm_mic_2_en(),
+            capture_edge: pdm_config_0.pdm_pad_0_cap_edge(),
+            rate: pdm_config_0.pdm_rate_pad_0(),
+            clock_error_detection: !pdm_config_0.dis_pdm_mic_clk_err_pad_0(),
+            clock_gating: pdm_config_0.pdm_gate_pad_0(),
+            clock_pull_down: din_pd_pdm_config_3.wk_pulldown_pdmck_pad_0(),
+            data_pull_down: din_pd_pdm_config_3.wk_pulldown_pdmd_pad_0(),
+            i2s_mode: pwr_ctl.pdm_i_2_s_mode(),
+        }
+    }
+
+    pub(crate) fn pdm_config_0<'w>(&self, w: &'w mut pdm_config_0::W) -> &'w mut pdm_config_0::W {
+        w.pdm_mic_slv(self.clock_mode)
+            .pdm_mic_1_en(self.mic1)
+            .pdm_mic_2_en(self.mic2)
+            .pdm_pad_0_cap_edge(self.capture_edge)
+            .dis_pdm_mic_clk_err_pad_0(!self.clock_error_detection)
+            .pdm_rate_pad_0(self.rate)
+            .pdm_gate_pad_0(self.clock_gating)
+    }
+
+    pub(crate) fn din_pd_pdm_config_3<'w>(
+        &self,
+        w: &'w mut din_pd_pdm_config_3::W,
+    ) -> &'w mut din_pd_pdm_config_3::W {
+        w.wk_pulldown_pdmck_pad_0(self.clock_pull_down)
+            .wk_pulldown_pdmd_pad_0(self.data_pull_down)
+    }
+}
@@ -0,0 +1,240 @@
+//! Software recovery from faults the device does not retry on its own.
+//!
+//! Typically driven by [`FaultMonitor::next_fault`](super::monitor::FaultMonitor::next_fault):
+//!
+//! ```ignore
+//! loop {
+//!     let faults = monitor.next_fault().await?;
+//!     let now = Instant::now().as_millis();
+//!     let action = recovery.handle(monitor.amp(), &config, faults, now, &mut Delay).await?;
+//!     if action == RecoveryAction::GaveUp {
+//!         break;
+//!     }
+//! }
+//! ```
+
+use embedded_hal_async::delay::DelayNs;
+
+use super::{blocking, config::Tas2563Config, faults::Faults, Tas2563};
+use crate::ll::{self, Tas2563Interface};
+use crate::prelude::*;
+use crate::Error;
+
+/// Which faults to recover from and how often to try.
+///
+/// Faults that are neither in [`RecoveryPolicy::remute`] nor in [`RecoveryPolicy::reinit`] are
+/// ignored. When both apply, the device is re-initialized.
+#[derive(Debug, Clone)]
+pub struct RecoveryPolicy {
+    /// Faults recovered from by going through mute and back to the previous mode.
+    pub remute: Faults,
+    /// Faults recovered from by a full re-initialization, including the tuning blobs.
+    pub reinit: Faults,
+    /// Attempts within a single window after which recovery gives up.
+    pub max_attempts: u8,
+    /// Length of the window in milliseconds, starting at the first attempt.
+    pub window_ms: u64,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            remute: Faults::TDM_CLOCK_ERROR | Faults::VBAT_BROWNOUT | Faults::BROWNOUT_POWER_DOWN,
+            reinit: Faults::POWER_DOWN | Faults::VBAT_POR,
+            max_attempts: 3,
+            window_ms: 10_000,
+        }
+    }
+}
+
+/// What [`Recovery::handle`] did in response to a set of faults.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RecoveryAction {
+    /// None of the faults are covered by the policy.
+    Ignored,
+    /// Went through mute and back to the previous mode.
+    Remuted,
+    /// Re-initialized the device.
+    Reinitialized,
+    /// Too many attempts within the window, the device was left in software shutdown.
+    ///
+    /// No further attempts are made until [`Recovery::reset`].
+    GaveUp,
+}
+
+/// Keeps track of recovery attempts according to a [`RecoveryPolicy`].
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    policy: RecoveryPolicy,
+    window_start: u64,
+    attempts: u8,
+    gave_up: bool,
+}
+
+impl Recovery {
+    pub fn new(policy: RecoveryPolicy) -> Self {
+        Self {
+            policy,
+            window_start: 0,
+            attempts: 0,
+            gave_up: false,
+        }
+    }
+
+    pub fn policy(&self) -> &RecoveryPolicy {
+        &self.policy
+    }
+
+    /// Forget all attempts, also after having given up.
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.gave_up = false;
+    }
+
+    /// Decide on an action for `faults` and perform it.
+    ///
+    /// `now_ms` is a monotonic timestamp in milliseconds, used to count attempts within the
+    /// window. `config` is applied when re-initializing.
+    pub async fn handle<T: Tas2563Interface>(
+        &mut self,
+        amp: &mut Tas2563<T>,
+        config: &Tas2563Config<'_>,
+        faults: Faults,
+        now_ms: u64,
+        delay: &mut impl DelayNs,
+    ) -> Result<RecoveryAction, Error<T::Error>> {
+        let action = self.decide(faults, now_ms);
+
+        match action {
+            RecoveryAction::Ignored => {}
+            RecoveryAction::Remuted => {
+                // The device may have left the mode on its own, always write it back.
+                let mode = match amp.mode {
+                    Some(mode) => mode,
+                    None => amp.refresh_mode().await?,
+                };
+                amp.mode = None;
+                amp.write_mode(Mode::Mute).await?;
+                amp.write_mode(mode).await?;
+            }
+            RecoveryAction::Reinitialized => amp.init(config, delay).await?,
+            RecoveryAction::GaveUp => {
+                amp.mode = None;
+                amp.write_mode(Mode::SoftwareShutdown).await?;
+            }
+        }
+
+        Ok(action)
+    }
+
+    /// Blocking counterpart of [`Recovery::handle`].
+    pub fn handle_blocking<T: ll::blocking::Tas2563Interface>(
+        &mut self,
+        amp: &mut blocking::Tas2563<T>,
+        config: &Tas2563Config<'_>,
+        faults: Faults,
+        now_ms: u64,
+        delay: &mut impl embedded_hal::delay::DelayNs,
+    ) -> Result<RecoveryAction, Error<T::Error>> {
+        let action = self.decide(faults, now_ms);
+
+        match action {
+            RecoveryAction::Ignored => {}
+            RecoveryAction::Remuted => {
+                let mode = match amp.mode {
+                    Some(mode) => mode,
+                    None => amp.refresh_mode()?,
+                };
+                amp.mode = None;
+                amp.write_mode(Mode::Mute)?;
+                amp.write_mode(mode)?;
+            }
+            RecoveryAction::Reinitialized => amp.init(config, delay)?,
+            RecoveryAction::GaveUp => {
+                amp.mode = None;
+                amp.write_mode(Mode::SoftwareShutdown)?;
+            }
+        }
+
+        Ok(action)
+    }
+
+    fn decide(&mut self, faults: Faults, now_ms: u64) -> RecoveryAction {
+        let action = if faults.intersects(self.policy.reinit) {
+            RecoveryAction::Reinitialized
+        } else if faults.intersects(self.policy.remute) {
+            RecoveryAction::Remuted
+        } else {
+            return RecoveryAction::Ignored;
+        };
+
+        if self.gave_up {
+            return RecoveryAction::Ignored;
+        }
+
+        if self.attempts == 0 || now_ms.saturating_sub(self.window_start) >= self.policy.window_ms {
+            self.window_start = now_ms;
+            self.attempts = 0;
+        }
+
+        if self.attempts >= self.policy.max_attempts {
+            self.gave_up = true;
+            return RecoveryAction::GaveUp;
+        }
+
+        self.attempts += 1;
+        action
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decide() {
+        let mut recovery = Recovery::new(RecoveryPolicy {
+            max_attempts: 2,
+            window_ms: 1000,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            recovery.decide(Faults::OVER_TEMPERATURE, 0),
+            RecoveryAction::Ignored
+        );
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 0),
+            RecoveryAction::Remuted
+        );
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR | Faults::VBAT_POR, 500),
+            RecoveryAction::Reinitialized
+        );
+
+        // A new window starts after the previous one elapsed.
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 1000),
+            RecoveryAction::Remuted
+        );
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 1100),
+            RecoveryAction::Remuted
+        );
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 1200),
+            RecoveryAction::GaveUp
+        );
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 5000),
+            RecoveryAction::Ignored
+        );
+
+        recovery.reset();
+        assert_eq!(
+            recovery.decide(Faults::TDM_CLOCK_ERROR, 5000),
+            RecoveryAction::Remuted
+        );
+    }
+}
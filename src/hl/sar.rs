@@ -0,0 +1,131 @@
+//! Monitoring of the supplies and die temperature as measured by the SAR ADC.
+
+use crate::prelude::*;
+
+/// Single consistent set of SAR ADC conversions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SarReading {
+    pub pvdd: Millivolts,
+    pub vbat: Millivolts,
+    pub temperature: Celsius,
+}
+
+/// Levels at which [`SarMonitor`] raises its flags.
+#[derive(Debug, Clone)]
+pub struct SarThresholds {
+    /// Flag a low battery when the average VBAT drops below this level.
+    pub low_battery: Millivolts,
+    /// Flag over-temperature when the average temperature rises above this level.
+    pub over_temperature: Celsius,
+}
+
+impl Default for SarThresholds {
+    fn default() -> Self {
+        Self {
+            low_battery: Millivolts(3300),
+            over_temperature: Celsius(120),
+        }
+    }
+}
+
+/// Averaged readings and the flags derived from them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SarStatus {
+    pub average: SarReading,
+    pub low_battery: bool,
+    pub over_temperature: bool,
+}
+
+/// Running average over the last `N` readings, checked against [`SarThresholds`].
+#[derive(Debug, Clone)]
+pub struct SarMonitor<const N: usize> {
+    thresholds: SarThresholds,
+    readings: [SarReading; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> SarMonitor<N> {
+    /// Monitor averaging over `N` readings, where a zero `N` fails to compile.
+    pub fn new(thresholds: SarThresholds) -> Self {
+        const { assert!(N > 0, "SarMonitor needs room for at least one reading") };
+        Self {
+            thresholds,
+            readings: [SarReading::default(); N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    pub fn thresholds(&self) -> &SarThresholds {
+        &self.thresholds
+    }
+
+    /// Forget all readings.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Add a reading, replacing the oldest once `N` readings have been collected.
+    pub fn update(&mut self, reading: SarReading) -> SarStatus {
+        self.readings[self.next] = reading;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+
+        let average = self.average();
+        SarStatus {
+            average,
+            low_battery: average.vbat < self.thresholds.low_battery,
+            over_temperature: average.temperature > self.thresholds.over_temperature,
+        }
+    }
+
+    fn average(&self) -> SarReading {
+        let readings = &self.readings[..self.len];
+        let n = self.len as i32;
+        let sum = |f: fn(&SarReading) -> i32| readings.iter().map(f).sum::<i32>() / n;
+
+        SarReading {
+            pvdd: Millivolts(sum(|r| r.pvdd.0 as i32) as u16),
+            vbat: Millivolts(sum(|r| r.vbat.0 as i32) as u16),
+            temperature: Celsius(sum(|r| r.temperature.0 as i32) as i16),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reading(vbat: u16, temperature: i16) -> SarReading {
+        SarReading {
+            pvdd: Millivolts(vbat),
+            vbat: Millivolts(vbat),
+            temperature: Celsius(temperature),
+        }
+    }
+
+    #[test]
+    fn running_average() {
+        let mut monitor = SarMonitor::<2>::new(SarThresholds {
+            low_battery: Millivolts(3500),
+            over_temperature: Celsius(100),
+        });
+
+        let status = monitor.update(reading(3600, 90));
+        assert_eq!(status.average, reading(3600, 90));
+        assert!(!status.low_battery && !status.over_temperature);
+
+        let status = monitor.update(reading(3300, 120));
+        assert_eq!(status.average, reading(3450, 105));
+        assert!(status.low_battery && status.over_temperature);
+
+        // The first reading drops out.
+        let status = monitor.update(reading(3800, 60));
+        assert_eq!(status.average, reading(3550, 90));
+        assert!(!status.low_battery && !status.over_temperature);
+    }
+}
@@ -0,0 +1,610 @@
+//! Layout of the TDM serial audio interface.
+//!
+//! A [`TdmConfig`] can only be obtained through [`TdmConfigBuilder::build`], which rejects layouts
+//! the device cannot play or transmit correctly.
+
+use super::clock::Clocking;
+use crate::ll::registers::{
+    clock_configuration, tdm_cfg_0, tdm_cfg_1, tdm_cfg_10, tdm_cfg_2, tdm_cfg_3, tdm_cfg_4,
+    tdm_cfg_5, tdm_cfg_6, tdm_cfg_7, tdm_cfg_8, tdm_cfg_9,
+};
+use crate::prelude::*;
+
+/// Highest RX offset in SBCLK cycles.
+const RX_OFFSET_MAX: u8 = 31;
+/// Highest TX offset in SBCLK cycles.
+const TX_OFFSET_MAX: u8 = 7;
+/// Amount of RX slots that can be addressed.
+const RX_SLOTS: u8 = 16;
+/// Amount of 8 bit TX slots that can be addressed.
+const TX_SLOTS: u8 = 64;
+
+/// Data the device can transmit on the serial interface.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TxData {
+    VoltageSense,
+    CurrentSense,
+    Vbat,
+    Temperature,
+    Gain,
+    Boost,
+}
+
+impl core::fmt::Display for TxData {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TxData::VoltageSense => write!(f, "voltage sense"),
+            TxData::CurrentSense => write!(f, "current sense"),
+            TxData::Vbat => write!(f, "VBAT"),
+            TxData::Temperature => write!(f, "temperature"),
+            TxData::Gain => write!(f, "gain"),
+            TxData::Boost => write!(f, "boost"),
+        }
+    }
+}
+
+/// Reasons a TDM layout can be rejected.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TdmError {
+    /// The SBCLK to FSYNC ratio is not one supported by the device.
+    ReservedSbclkFsRatio,
+    /// The RX word length exceeds the RX slot length.
+    WordLongerThanSlot,
+    /// The RX offset exceeds 31 SBCLK cycles.
+    RxOffsetOutOfRange,
+    /// The TX offset exceeds 7 SBCLK cycles.
+    TxOffsetOutOfRange,
+    /// An RX slot number exceeds 15.
+    RxSlotOutOfRange,
+    /// An RX slot that is played ends after the frame.
+    RxSlotOutsideFrame,
+    /// A TX slot number exceeds 63.
+    TxSlotOutOfRange(TxData),
+    /// A transmitted value ends after the frame.
+    TxSlotOutsideFrame(TxData),
+    /// Two transmitted values share (part of) a slot.
+    TxSlotsOverlap(TxData, TxData),
+}
+
+impl core::fmt::Display for TdmError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TdmError::ReservedSbclkFsRatio => write!(f, "reserved SBCLK to FSYNC ratio"),
+            TdmError::WordLongerThanSlot => write!(f, "RX word length exceeds the slot length"),
+            TdmError::RxOffsetOutOfRange => {
+                write!(f, "RX offset exceeds {} cycles", RX_OFFSET_MAX)
+            }
+            TdmError::TxOffsetOutOfRange => {
+                write!(f, "TX offset exceeds {} cycles", TX_OFFSET_MAX)
+            }
+            TdmError::RxSlotOutOfRange => write!(f, "RX slot exceeds {}", RX_SLOTS - 1),
+            TdmError::RxSlotOutsideFrame => write!(f, "RX slot ends after the frame"),
+            TdmError::TxSlotOutOfRange(d) => write!(f, "{} slot exceeds {}", d, TX_SLOTS - 1),
+            TdmError::TxSlotOutsideFrame(d) => write!(f, "{} slot ends after the frame", d),
+            TdmError::TxSlotsOverlap(a, b) => write!(f, "{} and {} slots overlap", a, b),
+        }
+    }
+}
+
+impl core::error::Error for TdmError {}
+
+/// Validated layout of the TDM serial audio interface, including the clocking derived from it.
+///
+/// Receive slots are counted in units of the RX slot length, transmit slots in 8 bit units. A
+/// transmit slot set to `None` is not transmitted.
+///
+/// The default describes an I2S bus (TDM with two 32 bit slots) at 48kHz with 24 bit samples,
+/// playing the left channel and returning 16 bit current and voltage sense data in the left and
+/// right slots respectively.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TdmConfig {
+    frame_start: FrameStart,
+    sample_rate: SampRate,
+    sbclk_fs_ratio: SbclkFsRatio,
+    auto_clock: bool,
+    rx_edge: RxEdge,
+    rx_offset: u8,
+    rx_justify: RxJustify,
+    rx_slot_length: RxSlen,
+    rx_word_length: RxWlen,
+    rx_slot_config: RxScfg,
+    rx_slot_left: u8,
+    rx_slot_right: u8,
+    tx_edge: TxEdge,
+    tx_offset: u8,
+    tx_fill: TxFill,
+    ivmon_length: IvmonLen,
+    vsns_slot: Option<u8>,
+    isns_slot: Option<u8>,
+    vbat_slot: Option<u8>,
+    vbat_length: VbatSlen,
+    temp_slot: Option<u8>,
+    gain_slot: Option<u8>,
+    bst_slot: Option<u8>,
+}
+
+impl Default for TdmConfig {
+    fn default() -> Self {
+        Self {
+            frame_start: FrameStart::HighToLow,
+            sample_rate: SampRate::Rate48Khz,
+            sbclk_fs_ratio: SbclkFsRatio::Ratio64,
+            auto_clock: true,
+            rx_edge: RxEdge::RisingEdge,
+            rx_offset: 1,
+            rx_justify: RxJustify::Left,
+            rx_slot_length: RxSlen::Length32Bits,
+            rx_word_length: RxWlen::Length24Bits,
+            rx_slot_config: RxScfg::MonoLeftChannel,
+            rx_slot_left: 0,
+            rx_slot_right: 1,
+            tx_edge: TxEdge::FallingEdge,
+            tx_offset: 1,
+            tx_fill: TxFill::Transmit0,
+            ivmon_length: IvmonLen::Length16Bits,
+            vsns_slot: Some(4),
+            isns_slot: Some(0),
+            vbat_slot: None,
+            vbat_length: VbatSlen::Truncate8Bits,
+            temp_slot: None,
+            gain_slot: None,
+            bst_slot: None,
+        }
+    }
+}
+
+impl TdmConfig {
+    /// Start a layout from the defaults.
+    pub fn builder() -> TdmConfigBuilder {
+        TdmConfigBuilder {
+            config: Self::default(),
+        }
+    }
+
+    /// Continue from this layout.
+    pub fn to_builder(&self) -> TdmConfigBuilder {
+        TdmConfigBuilder {
+            config: self.clone(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> SampRate {
+        self.sample_rate
+    }
+
+    /// Length of a frame in SBCLK cycles.
+    pub fn sbclk_fs_ratio(&self) -> SbclkFsRatio {
+        self.sbclk_fs_ratio
+    }
+
+    pub fn auto_clock(&self) -> bool {
+        self.auto_clock
+    }
+
+    /// Clocking as programmed into the device.
+    pub fn clocking(&self) -> Clocking {
+        if self.auto_clock {
+            Clocking::Auto
+        } else {
+            Clocking::Manual {
+                sample_rate: self.sample_rate,
+                sbclk_fs_ratio: self.sbclk_fs_ratio,
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), TdmError> {
+        let frame = frame_bits(self.sbclk_fs_ratio).ok_or(TdmError::ReservedSbclkFsRatio)?;
+
+        let slot = slot_bits(self.rx_slot_length);
+        if word_bits(self.rx_word_length) > slot {
+            return Err(TdmError::WordLongerThanSlot);
+        }
+        if self.rx_offset > RX_OFFSET_MAX {
+            return Err(TdmError::RxOffsetOutOfRange);
+        }
+        if self.tx_offset > TX_OFFSET_MAX {
+            return Err(TdmError::TxOffsetOutOfRange);
+        }
+        if self.rx_slot_left >= RX_SLOTS || self.rx_slot_right >= RX_SLOTS {
+            return Err(TdmError::RxSlotOutOfRange);
+        }
+
+        // Offsets shift the whole frame, as with I2S where the last bit of the frame spills over
+        // into the next. They are therefore not taken into account when checking the frame length.
+        //
+        // In mono with offset mode the slot follows from the device address instead.
+        let played = match self.rx_slot_config {
+            RxScfg::MonoWithOffset => [None, None],
+            RxScfg::MonoLeftChannel => [Some(self.rx_slot_left), None],
+            RxScfg::MonoRightChannel => [None, Some(self.rx_slot_right)],
+            RxScfg::StereoDownmix => [Some(self.rx_slot_left), Some(self.rx_slot_right)],
+        };
+        for rx in played.into_iter().flatten() {
+            if (u16::from(rx) + 1) * slot > frame {
+                return Err(TdmError::RxSlotOutsideFrame);
+            }
+        }
+
+        let tx = self.tx_slots();
+        for (i, (data, slot, width)) in tx.iter().enumerate() {
+            let Some(slot) = *slot else {
+                continue;
+            };
+            if slot >= TX_SLOTS {
+                return Err(TdmError::TxSlotOutOfRange(*data));
+            }
+            if (u16::from(slot) + u16::from(*width)) * 8 > frame {
+                return Err(TdmError::TxSlotOutsideFrame(*data));
+            }
+
+            for (other, other_slot, other_width) in &tx[..i] {
+                let Some(other_slot) = *other_slot else {
+                    continue;
+                };
+                if slot < other_slot + other_width && other_slot < slot + width {
+                    return Err(TdmError::TxSlotsOverlap(*other, *data));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// All transmitted values with their slot and their width in 8 bit slots.
+    fn tx_slots(&self) -> [(TxData, Option<u8>, u8); 6] {
+        let ivmon = match self.ivmon_length {
+            IvmonLen::Length8Bits => 1,
+            IvmonLen::Length16Bits => 2,
+            IvmonLen::Length24Bits => 3,
+            IvmonLen::Length32Bits => 4,
+        };
+        let vbat = match self.vbat_length {
+            VbatSlen::Truncate8Bits => 1,
+            VbatSlen::LeftJustify16Bits => 2,
+        };
+
+        [
+            (TxData::VoltageSense, self.vsns_slot, ivmon),
+            (TxData::CurrentSense, self.isns_slot, ivmon),
+            (TxData::Vbat, self.vbat_slot, vbat),
+            (TxData::Temperature, self.temp_slot, 1),
+            (TxData::Gain, self.gain_slot, 1),
+            (TxData::Boost, self.bst_slot, 1),
+        ]
+    }
+}
+
+fn frame_bits(ratio: SbclkFsRatio) -> Option<u16> {
+    Some(match ratio {
+        SbclkFsRatio::Ratio16 => 16,
+        SbclkFsRatio::Ratio24 => 24,
+        SbclkFsRatio::Ratio32 => 32,
+        SbclkFsRatio::Ratio48 => 48,
+        SbclkFsRatio::Ratio64 => 64,
+        SbclkFsRatio::Ratio96 => 96,
+        SbclkFsRatio::Ratio128 => 128,
+        SbclkFsRatio::Ratio192 => 192,
+        SbclkFsRatio::Ratio256 => 256,
+        SbclkFsRatio::Ratio384 => 384,
+        SbclkFsRatio::Ratio512 => 512,
+        SbclkFsRatio::Reserved(_) => return None,
+    })
+}
+
+fn slot_bits(length: RxSlen) -> u16 {
+    match length {
+        RxSlen::Length16Bits => 16,
+        RxSlen::Length24Bits => 24,
+        RxSlen::Length32Bits => 32,
+    }
+}
+
+fn word_bits(length: RxWlen) -> u16 {
+    match length {
+        RxWlen::Length16Bits => 16,
+        RxWlen::Length20Bits => 20,
+        RxWlen::Length24Bits => 24,
+        RxWlen::Length32Bits => 32,
+    }
+}
+
+/// Builder for [`TdmConfig`], see [`TdmConfig::builder`].
+#[derive(Debug, Clone)]
+pub struct TdmConfigBuilder {
+    config: TdmConfig,
+}
+
+impl TdmConfigBuilder {
+    /// Check the layout as a whole.
+    pub fn build(self) -> Result<TdmConfig, TdmError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+
+    pub fn frame_start(mut self, frame_start: FrameStart) -> Self {
+        self.config.frame_start = frame_start;
+        self
+    }
+
+    pub fn sample_rate(mut self, sample_rate: SampRate) -> Self {
+        self.config.sample_rate = sample_rate;
+        self
+    }
+
+    /// Length of a frame in SBCLK cycles, against which all slots are checked.
+    ///
+    /// Only programmed into the device when automatic clocking is disabled.
+    pub fn sbclk_fs_ratio(mut self, ratio: SbclkFsRatio) -> Self {
+        self.config.sbclk_fs_ratio = ratio;
+        self
+    }
+
+    /// Let the device detect the sample rate and SBCLK to FSYNC ratio.
+    pub fn auto_clock(mut self, auto: bool) -> Self {
+        self.config.auto_clock = auto;
+        self
+    }
+
+    /// Either let the device detect the clocking, or set the sample rate and SBCLK to FSYNC ratio.
+    pub fn clocking(mut self, clocking: Clocking) -> Self {
+        match clocking {
+            Clocking::Auto => self.config.auto_clock = true,
+            Clocking::Manual {
+                sample_rate,
+                sbclk_fs_ratio,
+            } => {
+                self.config.auto_clock = false;
+                self.config.sample_rate = sample_rate;
+                self.config.sbclk_fs_ratio = sbclk_fs_ratio;
+            }
+        }
+        self
+    }
+
+    pub fn rx_edge(mut self, edge: RxEdge) -> Self {
+        self.config.rx_edge = edge;
+        self
+    }
+
+    /// Offset of slot 0 with respect to the frame start, in SBCLK cycles.
+    pub fn rx_offset(mut self, offset: u8) -> Self {
+        self.config.rx_offset = offset;
+        self
+    }
+
+    pub fn rx_justify(mut self, justify: RxJustify) -> Self {
+        self.config.rx_justify = justify;
+        self
+    }
+
+    pub fn rx_slot_length(mut self, length: RxSlen) -> Self {
+        self.config.rx_slot_length = length;
+        self
+    }
+
+    pub fn rx_word_length(mut self, length: RxWlen) -> Self {
+        self.config.rx_word_length = length;
+        self
+    }
+
+    pub fn rx_slot_config(mut self, config: RxScfg) -> Self {
+        self.config.rx_slot_config = config;
+        self
+    }
+
+    pub fn rx_slots(mut self, left: u8, right: u8) -> Self {
+        self.config.rx_slot_left = left;
+        self.config.rx_slot_right = right;
+        self
+    }
+
+    pub fn tx_edge(mut self, edge: TxEdge) -> Self {
+        self.config.tx_edge = edge;
+        self
+    }
+
+    /// Offset of slot 0 with respect to the frame start, in SBCLK cycles.
+    pub fn tx_offset(mut self, offset: u8) -> Self {
+        self.config.tx_offset = offset;
+        self
+    }
+
+    pub fn tx_fill(mut self, fill: TxFill) -> Self {
+        self.config.tx_fill = fill;
+        self
+    }
+
+    /// Length of both the voltage and the current sense values.
+    pub fn ivmon_length(mut self, length: IvmonLen) -> Self {
+        self.config.ivmon_length = length;
+        self
+    }
+
+    pub fn vsns_slot(mut self, slot: Option<u8>) -> Self {
+        self.config.vsns_slot = slot;
+        self
+    }
+
+    pub fn isns_slot(mut self, slot: Option<u8>) -> Self {
+        self.config.isns_slot = slot;
+        self
+    }
+
+    pub fn vbat_slot(mut self, slot: Option<u8>, length: VbatSlen) -> Self {
+        self.config.vbat_slot = slot;
+        self.config.vbat_length = length;
+        self
+    }
+
+    pub fn temp_slot(mut self, slot: Option<u8>) -> Self {
+        self.config.temp_slot = slot;
+        self
+    }
+
+    pub fn gain_slot(mut self, slot: Option<u8>) -> Self {
+        self.config.gain_slot = slot;
+        self
+    }
+
+    pub fn bst_slot(mut self, slot: Option<u8>) -> Self {
+        self.config.bst_slot = slot;
+        self
+    }
+}
+
+impl TdmConfig {
+    pub(crate) fn tdm_cfg_0<'w>(&self, w: &'w mut tdm_cfg_0::W) -> &'w mut tdm_cfg_0::W {
+        w.frame_start(self.frame_start)
+            .samp_rate(self.sample_rate)
+            .auto_rate(!self.auto_clock)
+    }
+
+    pub(crate) fn tdm_cfg_1<'w>(&self, w: &'w mut tdm_cfg_1::W) -> &'w mut tdm_cfg_1::W {
+        w.rx_edge(self.rx_edge)
+            .rx_offset(self.rx_offset)
+            .rx_justify(self.rx_justify)
+    }
+
+    pub(crate) fn tdm_cfg_2<'w>(&self, w: &'w mut tdm_cfg_2::W) -> &'w mut tdm_cfg_2::W {
+        w.rx_slen(self.rx_slot_length)
+            .rx_wlen(self.rx_word_length)
+            .rx_scfg(self.rx_slot_config)
+            .ivmon_len(self.ivmon_length)
+    }
+
+    pub(crate) fn tdm_cfg_3<'w>(&self, w: &'w mut tdm_cfg_3::W) -> &'w mut tdm_cfg_3::W {
+        w.rx_slot_l(self.rx_slot_left).rx_slot_r(self.rx_slot_right)
+    }
+
+    pub(crate) fn tdm_cfg_4<'w>(&self, w: &'w mut tdm_cfg_4::W) -> &'w mut tdm_cfg_4::W {
+        w.tx_edge(self.tx_edge)
+            .tx_offset(self.tx_offset)
+            .tx_fill(self.tx_fill)
+    }
+
+    pub(crate) fn tdm_cfg_5<'w>(&self, w: &'w mut tdm_cfg_5::W) -> &'w mut tdm_cfg_5::W {
+        w.vsns_slot(self.vsns_slot.unwrap_or_default())
+            .vsns_tx(self.vsns_slot.is_some())
+    }
+
+    pub(crate) fn tdm_cfg_6<'w>(&self, w: &'w mut tdm_cfg_6::W) -> &'w mut tdm_cfg_6::W {
+        w.isns_slot(self.isns_slot.unwrap_or_default())
+            .isns_tx(self.isns_slot.is_some())
+    }
+
+    pub(crate) fn tdm_cfg_7<'w>(&self, w: &'w mut tdm_cfg_7::W) -> &'w mut tdm_cfg_7::W {
+        w.vbat_slot(self.vbat_slot.unwrap_or_default())
+            .vbat_tx(self.vbat_slot.is_some())
+            .vbat_slen(self.vbat_length)
+    }
+
+    pub(crate) fn tdm_cfg_8<'w>(&self, w: &'w mut tdm_cfg_8::W) -> &'w mut tdm_cfg_8::W {
+        w.temp_slot(self.temp_slot.unwrap_or_default())
+            .temp_tx(self.temp_slot.is_some())
+    }
+
+    pub(crate) fn tdm_cfg_9<'w>(&self, w: &'w mut tdm_cfg_9::W) -> &'w mut tdm_cfg_9::W {
+        w.gain_slot(self.gain_slot.unwrap_or_default())
+            .gain_tx(self.gain_slot.is_some())
+    }
+
+    pub(crate) fn tdm_cfg_10<'w>(&self, w: &'w mut tdm_cfg_10::W) -> &'w mut tdm_cfg_10::W {
+        w.bst_slot(self.bst_slot.unwrap_or_default())
+            .bst_tx(self.bst_slot.is_some())
+    }
+
+    pub(crate) fn clock_configuration<'w>(
+        &self,
+        w: &'w mut clock_configuration::W,
+    ) -> &'w mut clock_configuration::W {
+        w.auto_clk(self.auto_clock)
+            .sbclk_fs_ratio(self.sbclk_fs_ratio)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_is_valid() {
+        assert_eq!(TdmConfig::builder().build(), Ok(TdmConfig::default()));
+    }
+
+    #[test]
+    fn word_longer_than_slot() {
+        let result = TdmConfig::builder()
+            .rx_slot_length(RxSlen::Length16Bits)
+            .rx_word_length(RxWlen::Length24Bits)
+            .build();
+        assert_eq!(result, Err(TdmError::WordLongerThanSlot));
+    }
+
+    #[test]
+    fn rx_outside_frame() {
+        // The right slot is only played for stereo.
+        let builder = TdmConfig::builder().rx_slots(0, 2);
+        assert!(builder.clone().build().is_ok());
+        assert_eq!(
+            builder.rx_slot_config(RxScfg::StereoDownmix).build(),
+            Err(TdmError::RxSlotOutsideFrame)
+        );
+        assert!(TdmConfig::builder()
+            .sbclk_fs_ratio(SbclkFsRatio::Ratio128)
+            .rx_slots(0, 2)
+            .rx_slot_config(RxScfg::StereoDownmix)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn tx_layout() {
+        // 24 bit current sense in slot 0 runs into voltage sense in slot 2.
+        assert_eq!(
+            TdmConfig::builder()
+                .ivmon_length(IvmonLen::Length24Bits)
+                .isns_slot(Some(0))
+                .vsns_slot(Some(2))
+                .build(),
+            Err(TdmError::TxSlotsOverlap(
+                TxData::VoltageSense,
+                TxData::CurrentSense
+            ))
+        );
+
+        // 16 bit VBAT overlaps with the temperature in its second byte.
+        assert_eq!(
+            TdmConfig::builder()
+                .vbat_slot(Some(2), VbatSlen::LeftJustify16Bits)
+                .temp_slot(Some(3))
+                .build(),
+            Err(TdmError::TxSlotsOverlap(TxData::Vbat, TxData::Temperature))
+        );
+
+        // A 64 cycle frame has room for 8 slots.
+        assert!(TdmConfig::builder().gain_slot(Some(7)).build().is_ok());
+        assert_eq!(
+            TdmConfig::builder()
+                .vbat_slot(Some(7), VbatSlen::LeftJustify16Bits)
+                .build(),
+            Err(TdmError::TxSlotOutsideFrame(TxData::Vbat))
+        );
+        assert_eq!(
+            TdmConfig::builder().bst_slot(Some(64)).build(),
+            Err(TdmError::TxSlotOutOfRange(TxData::Boost))
+        );
+    }
+
+    #[test]
+    fn reserved_ratio() {
+        assert_eq!(
+            TdmConfig::builder()
+                .sbclk_fs_ratio(SbclkFsRatio::Reserved(0x0f))
+                .build(),
+            Err(TdmError::ReservedSbclkFsRatio)
+        );
+    }
+}
@@ -0,0 +1,855 @@
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    digital,
+    i2c::{Mock, Transaction},
+    MockError,
+};
+
+use super::{
+    asi2::Asi2Config,
+    blocking,
+    bop::BopConfig,
+    clock::Clocking,
+    config::Tas2563Config,
+    derating::{Derating, DeratingHysteresis, DeratingRule, DeratingTarget},
+    diagnostics::LoadDiagnostics,
+    faults::Faults,
+    limiter::LimiterConfig,
+    monitor::FaultMonitor,
+    pdm::PdmConfig,
+    recovery::{Recovery, RecoveryAction, RecoveryPolicy},
+    sar::SarReading,
+    volume::VolumeRamp,
+    Tas2563,
+};
+use crate::{bulk::BulkError, ll::i2c::Address, prelude::*, Error};
+
+fn reg(register: u8, value: u8) -> Transaction {
+    Transaction::write(Address::Global as u8, vec![register, value])
+}
+
+fn read(register: u8, value: u8) -> Transaction {
+    Transaction::write_read(Address::Global as u8, vec![register], vec![value])
+}
+
+/// Tuning blob applied by [`init_default_expectations`].
+const TUNING: &[&[u8]] = &[&[0x00, 0x01, 0xfe, 0x01, 0x08, 0xaa]];
+
+/// Initialization with the default configuration and [`TUNING`], from an unknown book and page.
+fn init_default_expectations() -> Vec<Transaction> {
+    vec![
+        // Software reset
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x01, 0x01),
+        // Software shutdown with sensing powered up, after re-selecting book and page
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x02, 0x02),
+        // Amp level 16dBV with DC blocker
+        reg(0x03, 0x20),
+        // Spread spectrum and all retries
+        reg(0x04, 0xf6),
+        // I2S at 48kHz, 24 bit words in 32 bit slots, left channel, I/V sense on slot 0 and 4
+        reg(0x06, 0x09),
+        reg(0x07, 0x02),
+        reg(0x08, 0x5a),
+        reg(0x09, 0x10),
+        reg(0x0a, 0x03),
+        reg(0x0b, 0x44),
+        reg(0x0c, 0x40),
+        reg(0x0d, 0x00),
+        reg(0x0e, 0x00),
+        reg(0x0f, 0x00),
+        reg(0x10, 0x00),
+        // Automatic clocking, expecting 64 SBCLK cycles per frame
+        reg(0x38, 0x11),
+        // IRQZ latched and active low
+        reg(0x30, 0x19),
+        reg(0x32, 0x80),
+        // Boost at reset values
+        reg(0x33, 0x34),
+        reg(0x34, 0x4b),
+        reg(0x35, 0x74),
+        reg(0x40, 0x76),
+        // PDM microphones disabled
+        reg(0x41, 0x00),
+        read(0x42, 0x00),
+        reg(0x42, 0x00),
+        // Tuning blob
+        reg(0x00, 0x01),
+        reg(0x08, 0xaa),
+        // Mute, after returning to page 0
+        reg(0x00, 0x00),
+        reg(0x02, 0x01),
+    ]
+}
+
+#[async_std::test]
+async fn init_default() {
+    let mut i2c = Mock::new(&init_default_expectations());
+
+    let config = Tas2563Config {
+        tuning: TUNING,
+        ..Default::default()
+    };
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.init(&config, &mut NoopDelay).await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn init_invalid_tuning() {
+    let mut i2c = Mock::new(&[]);
+
+    let config = Tas2563Config {
+        tuning: &[&[0x00, 0x01], &[0x00]],
+        ..Default::default()
+    };
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(
+        hl.init(&config, &mut NoopDelay).await,
+        Err(Error::Bulk(BulkError::OddLength))
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn faults() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Only leave over temperature and over current unmasked
+        reg(0x1a, 0xfc),
+        reg(0x1b, 0xbf),
+        reg(0x1c, 0xff),
+        reg(0x1d, 0xff),
+        // Latched over current and TDM clock error, with reserved bits set
+        read(0x24, 0x06),
+        read(0x25, 0xc0),
+        read(0x26, 0x00),
+        read(0x27, 0x01),
+        // Clear latches, preserving the IRQZ configuration
+        read(0x30, 0x19),
+        reg(0x30, 0x1d),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.set_interrupt_mask(!(Faults::OVER_TEMPERATURE | Faults::OVER_CURRENT))
+        .await
+        .unwrap();
+    assert_eq!(
+        hl.latched_faults().await,
+        Ok(Faults::OVER_CURRENT | Faults::TDM_CLOCK_ERROR)
+    );
+    hl.clear_latched().await.unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn monitor() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Latched and active low
+        read(0x30, 0x18),
+        reg(0x30, 0x19),
+        read(0x32, 0x00),
+        reg(0x32, 0x80),
+        read(0x30, 0x19),
+        reg(0x30, 0x1d),
+        // Over temperature
+        read(0x24, 0x01),
+        read(0x25, 0x00),
+        read(0x26, 0x00),
+        read(0x27, 0x00),
+        read(0x30, 0x19),
+        reg(0x30, 0x1d),
+    ];
+    let mut i2c = Mock::new(&expectations);
+    let mut irqz = digital::Mock::new(&[
+        digital::Transaction::wait_for_state(digital::State::Low),
+        digital::Transaction::wait_for_state(digital::State::Low)
+            .with_error(MockError::Io(std::io::ErrorKind::Other)),
+    ]);
+
+    let hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    let mut monitor = FaultMonitor::new(hl, &mut irqz, IrqzPol::ActiveLow)
+        .await
+        .unwrap();
+    assert_eq!(monitor.next_fault().await, Ok(Faults::OVER_TEMPERATURE));
+    assert_eq!(
+        monitor.next_fault().await,
+        Err(Error::Pin(embedded_hal::digital::ErrorKind::Other))
+    );
+
+    i2c.done();
+    irqz.done();
+}
+
+#[async_std::test]
+async fn recovery_remute() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Unknown mode is read back first, active
+        read(0x02, 0x00),
+        // Mute and back to active
+        read(0x02, 0x00),
+        reg(0x02, 0x01),
+        read(0x02, 0x01),
+        reg(0x02, 0x00),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    let mut recovery = Recovery::new(RecoveryPolicy::default());
+    let config = Tas2563Config::default();
+    assert_eq!(
+        recovery
+            .handle(
+                &mut hl,
+                &config,
+                Faults::OVER_TEMPERATURE,
+                0,
+                &mut NoopDelay
+            )
+            .await,
+        Ok(RecoveryAction::Ignored)
+    );
+    assert_eq!(
+        recovery
+            .handle(&mut hl, &config, Faults::TDM_CLOCK_ERROR, 0, &mut NoopDelay)
+            .await,
+        Ok(RecoveryAction::Remuted)
+    );
+    assert_eq!(hl.mode(), Some(Mode::Active));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn recovery_reinit() {
+    let mut expectations = init_default_expectations();
+    // Giving up leaves the device in software shutdown
+    expectations.extend([read(0x02, 0x01), reg(0x02, 0x02)]);
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    let mut recovery = Recovery::new(RecoveryPolicy {
+        max_attempts: 1,
+        ..Default::default()
+    });
+    let config = Tas2563Config {
+        tuning: TUNING,
+        ..Default::default()
+    };
+    assert_eq!(
+        recovery
+            .handle(&mut hl, &config, Faults::VBAT_POR, 0, &mut NoopDelay)
+            .await,
+        Ok(RecoveryAction::Reinitialized)
+    );
+    assert_eq!(
+        recovery
+            .handle(&mut hl, &config, Faults::VBAT_POR, 100, &mut NoopDelay)
+            .await,
+        Ok(RecoveryAction::GaveUp)
+    );
+    assert_eq!(hl.mode(), Some(Mode::SoftwareShutdown));
+
+    i2c.done();
+}
+
+#[test]
+fn recovery_reinit_blocking() {
+    let mut expectations = init_default_expectations();
+    expectations.extend([read(0x02, 0x01), reg(0x02, 0x02)]);
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = blocking::Tas2563::new_i2c(&mut i2c, Address::Global);
+    let mut recovery = Recovery::new(RecoveryPolicy {
+        max_attempts: 1,
+        ..Default::default()
+    });
+    let config = Tas2563Config {
+        tuning: TUNING,
+        ..Default::default()
+    };
+    assert_eq!(
+        recovery.handle_blocking(&mut hl, &config, Faults::VBAT_POR, 0, &mut NoopDelay),
+        Ok(RecoveryAction::Reinitialized)
+    );
+    assert_eq!(
+        recovery.handle_blocking(&mut hl, &config, Faults::VBAT_POR, 100, &mut NoopDelay),
+        Ok(RecoveryAction::GaveUp)
+    );
+    assert_eq!(hl.mode(), Some(Mode::SoftwareShutdown));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn gain() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // 12dBV, keeping the DC blocker disabled
+        read(0x03, 0x40),
+        reg(0x03, 0x50),
+        read(0x03, 0x50),
+        read(0x03, 0x00),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(hl.set_gain_db(11.9).await, Ok(AmpLevel::Amp12DBv0));
+    assert_eq!(hl.gain_db().await, Ok(Some(12.0)));
+    assert_eq!(hl.gain_db().await, Ok(None));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn volume_ramp() {
+    let expectations = [
+        // Currently muted
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x00, 0x02),
+        Transaction::write_read(
+            Address::Global as u8,
+            vec![0x0c],
+            vec![0x00, 0x00, 0x00, 0x00],
+        ),
+        // Ramp up from -110dB in 2dB steps
+        Transaction::write(Address::Global as u8, vec![0x0c, 0x00, 0x00, 0x10, 0xb3]),
+        Transaction::write(Address::Global as u8, vec![0x0c, 0x00, 0x00, 0x15, 0x05]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let ramp = VolumeRamp {
+        step_db: 2.0,
+        interval_ms: 1,
+    };
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.ramp_volume_db(-106.0, &ramp, &mut NoopDelay)
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn mode_tracking() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Mute from shutdown, only once
+        read(0x02, 0x02),
+        reg(0x02, 0x01),
+        // Shutdown and wake back into mute
+        read(0x02, 0x01),
+        reg(0x02, 0x02),
+        read(0x02, 0x02),
+        reg(0x02, 0x01),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(hl.mode(), None);
+    hl.mute(&mut NoopDelay).await.unwrap();
+    hl.mute(&mut NoopDelay).await.unwrap();
+    assert_eq!(hl.mode(), Some(Mode::Mute));
+    hl.shutdown(&mut NoopDelay).await.unwrap();
+    hl.shutdown(&mut NoopDelay).await.unwrap();
+    assert_eq!(hl.mode(), Some(Mode::SoftwareShutdown));
+    hl.wake(&mut NoopDelay).await.unwrap();
+    assert_eq!(hl.mode(), Some(Mode::Mute));
+
+    i2c.done();
+}
+
+fn latched(ltch1: u8) -> [Transaction; 4] {
+    [
+        read(0x24, 0x00),
+        read(0x25, ltch1),
+        read(0x26, 0x00),
+        read(0x27, 0x00),
+    ]
+}
+
+/// Load diagnostics from mute with the default configuration, up to the first poll.
+fn load_diagnostics_start() -> Vec<Transaction> {
+    vec![
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Muted before
+        read(0x02, 0x01),
+        // Averaging and auxiliary clock
+        read(0x3d, 0x00),
+        reg(0x3d, 0x08),
+        read(0x30, 0x19),
+        reg(0x30, 0x1d),
+        read(0x02, 0x01),
+        reg(0x02, 0x03),
+    ]
+}
+
+/// Return to mute and clear the latched faults.
+fn load_diagnostics_end() -> [Transaction; 4] {
+    [
+        read(0x02, 0x03),
+        reg(0x02, 0x01),
+        read(0x30, 0x19),
+        reg(0x30, 0x1d),
+    ]
+}
+
+#[async_std::test]
+async fn load_diagnostics() {
+    let mut expectations = load_diagnostics_start();
+    expectations.extend(latched(0x00));
+    expectations.extend(latched(0x20));
+    expectations.extend(load_diagnostics_end());
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(
+        hl.load_diagnostics(&Default::default(), &mut NoopDelay)
+            .await,
+        Ok(LoadDiagnostics::Ok { ohms: None })
+    );
+    assert_eq!(hl.mode(), Some(Mode::Mute));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn load_diagnostics_bus_error() {
+    let mut expectations = load_diagnostics_start();
+    expectations.push(
+        Transaction::write_read(Address::Global as u8, vec![0x24], vec![0x00])
+            .with_error(ErrorKind::Other),
+    );
+    expectations.extend(load_diagnostics_end());
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(
+        hl.load_diagnostics(&Default::default(), &mut NoopDelay)
+            .await,
+        Err(Error::Bus(ErrorKind::Other))
+    );
+    assert_eq!(hl.mode(), Some(Mode::Mute));
+
+    i2c.done();
+}
+
+#[test]
+fn load_diagnostics_blocking() {
+    let mut expectations = load_diagnostics_start();
+    expectations.extend(latched(0x20));
+    expectations.extend(load_diagnostics_end());
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = blocking::Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(
+        hl.load_diagnostics(&Default::default(), &mut NoopDelay),
+        Ok(LoadDiagnostics::Ok { ohms: None })
+    );
+    assert_eq!(hl.mode(), Some(Mode::Mute));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn sar() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Hold conversions during readback
+        read(0x3d, 0x08),
+        reg(0x3d, 0x09),
+        Transaction::write_read(Address::Global as u8, vec![0x49], vec![0xb0, 0x00]),
+        Transaction::write_read(Address::Global as u8, vec![0x2a], vec![0x39, 0x80]),
+        read(0x2c, 0x76),
+        read(0x3d, 0x09),
+        reg(0x3d, 0x08),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(
+        hl.sar().await,
+        Ok(SarReading {
+            pvdd: Millivolts(11000),
+            vbat: Millivolts(3593),
+            temperature: Celsius(25),
+        })
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn sar_bus_error() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        read(0x3d, 0x08),
+        reg(0x3d, 0x09),
+        Transaction::write_read(Address::Global as u8, vec![0x49], vec![0xb0, 0x00])
+            .with_error(ErrorKind::Other),
+        // The hold is released nonetheless
+        read(0x3d, 0x09),
+        reg(0x3d, 0x08),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(hl.sar().await, Err(Error::Bus(ErrorKind::Other)));
+
+    i2c.done();
+}
+
+/// Held SAR readout at 11V PVDD, the given raw VBAT conversion and 25 degrees.
+fn sar_readout(vbat: [u8; 2]) -> [Transaction; 7] {
+    [
+        read(0x3d, 0x08),
+        reg(0x3d, 0x09),
+        Transaction::write_read(Address::Global as u8, vec![0x49], vec![0xb0, 0x00]),
+        Transaction::write_read(Address::Global as u8, vec![0x2a], vec![vbat[0], vbat[1]]),
+        read(0x2c, 0x76),
+        read(0x3d, 0x09),
+        reg(0x3d, 0x08),
+    ]
+}
+
+#[async_std::test]
+async fn derating_tick() {
+    let mut expectations = vec![reg(0x00, 0x00), reg(0x7f, 0x00)];
+    // 3593mV, the requested 0dB
+    expectations.extend(sar_readout([0x39, 0x80]));
+    expectations.extend([
+        reg(0x00, 0x02),
+        Transaction::write(Address::Global as u8, vec![0x0c, 0x40, 0x00, 0x00, 0x00]),
+    ]);
+    // 3390mV, limited to -6dB
+    expectations.push(reg(0x00, 0x00));
+    expectations.extend(sar_readout([0x36, 0x40]));
+    expectations.extend([
+        reg(0x00, 0x02),
+        Transaction::write(Address::Global as u8, vec![0x0c, 0x20, 0x13, 0x73, 0x80]),
+    ]);
+    // 3546mV is within the hysteresis, the volume is not written again
+    expectations.push(reg(0x00, 0x00));
+    expectations.extend(sar_readout([0x38, 0xc0]));
+    let mut i2c = Mock::new(&expectations);
+
+    let rules = [DeratingRule {
+        vbat_below: Some(Millivolts(3500)),
+        temperature_above: None,
+        max_db: -6.0,
+    }];
+    let mut derating = Derating::new(
+        &rules,
+        DeratingTarget::Volume,
+        DeratingHysteresis::default(),
+        0.0,
+    );
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(derating.tick(&mut hl).await, Ok(0.0));
+    assert_eq!(derating.tick(&mut hl).await, Ok(-6.0));
+    assert_eq!(derating.tick(&mut hl).await, Ok(-6.0));
+
+    i2c.done();
+}
+
+#[test]
+fn derating_tick_blocking() {
+    let mut expectations = vec![reg(0x00, 0x00), reg(0x7f, 0x00)];
+    // 3593mV, the requested 16dBV
+    expectations.extend(sar_readout([0x39, 0x80]));
+    expectations.extend([read(0x03, 0x20), reg(0x03, 0x20)]);
+    // 3390mV, limited to 12dBV
+    expectations.extend(sar_readout([0x36, 0x40]));
+    expectations.extend([read(0x03, 0x20), reg(0x03, 0x10)]);
+    let mut i2c = Mock::new(&expectations);
+
+    let rules = [DeratingRule {
+        vbat_below: Some(Millivolts(3500)),
+        temperature_above: None,
+        max_db: 12.0,
+    }];
+    let mut derating = Derating::new(
+        &rules,
+        DeratingTarget::Gain,
+        DeratingHysteresis::default(),
+        16.0,
+    );
+
+    let mut hl = blocking::Tas2563::new_i2c(&mut i2c, Address::Global);
+    assert_eq!(derating.tick_blocking(&mut hl), Ok(16.0));
+    assert_eq!(derating.tick_blocking(&mut hl), Ok(12.0));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn limiter() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x12, 0x13),
+        reg(0x13, 0x76),
+        reg(0x00, 0x02),
+        Transaction::write(
+            Address::Global as u8,
+            vec![
+                0x14, 0x2d, 0x6a, 0x86, 0x80, 0x48, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x1c,
+                0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00,
+            ],
+        ),
+        reg(0x00, 0x00),
+        read(0x12, 0x13),
+        read(0x13, 0x76),
+        reg(0x00, 0x02),
+        // As written by the stock tuning
+        Transaction::write_read(
+            Address::Global as u8,
+            vec![0x14],
+            vec![
+                0x2d, 0x6a, 0x86, 0x6f, 0x47, 0x5c, 0x28, 0xf6, 0x16, 0x66, 0x66, 0x66, 0x1a, 0x66,
+                0x66, 0x66, 0x08, 0x00, 0x00, 0x00,
+            ],
+        ),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.set_limiter(&LimiterConfig::default()).await.unwrap();
+
+    let limiter = hl.limiter().await.unwrap();
+    let expected = LimiterConfig::default();
+    assert_eq!(limiter.enable, expected.enable);
+    assert_eq!(limiter.hold_time, expected.hold_time);
+    assert!(libm::fabsf(limiter.max_attenuation_db - 9.0) < 0.001);
+    assert!(libm::fabsf(limiter.threshold_max_v - 8.92) < 0.001);
+    assert!(libm::fabsf(limiter.threshold_min_v - 2.8) < 0.001);
+    assert!(libm::fabsf(limiter.inflection_point_v - 3.3) < 0.001);
+    assert_eq!(limiter.slope, 1.0);
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bop() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Enabled with brown out shutdown
+        reg(0x14, 0x11),
+        reg(0x15, 0x2e),
+        reg(0x00, 0x02),
+        // Maximum attenuation shared with the limiter
+        Transaction::write(Address::Global as u8, vec![0x14, 0x5a, 0x9d, 0xf7, 0x80]),
+        Transaction::write(
+            Address::Global as u8,
+            vec![0x28, 0x17, 0x33, 0x33, 0x40, 0x15, 0x99, 0x99, 0xa0],
+        ),
+        reg(0x00, 0x00),
+        read(0x14, 0x11),
+        read(0x15, 0x2e),
+        reg(0x00, 0x02),
+        // As written by the stock tuning
+        Transaction::write_read(
+            Address::Global as u8,
+            vec![0x28],
+            vec![0x17, 0x33, 0x33, 0x33, 0x15, 0x99, 0x99, 0x9a],
+        ),
+        Transaction::write_read(
+            Address::Global as u8,
+            vec![0x14],
+            vec![0x2d, 0x6a, 0x86, 0x6f],
+        ),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let config = BopConfig {
+        shutdown_threshold: Some(Millivolts(2700)),
+        max_attenuation_db: Some(3.0),
+        ..Default::default()
+    };
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.set_bop(&config).await.unwrap();
+    // The tuning limits to 9dB
+    let readback = hl.bop().await.unwrap();
+    assert!((readback.max_attenuation_db.unwrap() - 9.0).abs() < 0.01);
+    assert_eq!(
+        readback,
+        BopConfig {
+            max_attenuation_db: readback.max_attenuation_db,
+            ..config
+        }
+    );
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn clocking() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // 48kHz with 64 SBCLK cycles per frame
+        read(0x11, 0x24),
+        // Manual clocking disables both rate and ratio detection
+        read(0x06, 0x09),
+        reg(0x06, 0x19),
+        read(0x38, 0x11),
+        reg(0x38, 0x20),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    let detected = hl.detected_clocking().await.unwrap();
+    assert_eq!(detected.sample_rate(), Some(SampRate::Rate48Khz));
+    assert_eq!(detected.sbclk_fs_ratio(), Some(SbclkFsRatio::Ratio64));
+
+    hl.set_clocking(Clocking::Manual {
+        sample_rate: SampRate::Rate48Khz,
+        sbclk_fs_ratio: SbclkFsRatio::Ratio256,
+    })
+    .await
+    .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn unmute_when_locked() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // No clocks yet
+        read(0x11, 0x7f),
+        read(0x1f, 0x04),
+        read(0x21, 0x00),
+        // Locked for two polls in a row
+        read(0x11, 0x24),
+        read(0x1f, 0x00),
+        read(0x21, 0x00),
+        read(0x11, 0x24),
+        read(0x1f, 0x00),
+        read(0x21, 0x00),
+        read(0x02, 0x01),
+        reg(0x02, 0x00),
+        // Clocks gone, until the timeout
+        read(0x11, 0x7f),
+        read(0x1f, 0x04),
+        read(0x21, 0x00),
+        read(0x11, 0x7f),
+        read(0x1f, 0x04),
+        read(0x21, 0x00),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    let state = hl.unmute_when_locked(10, &mut NoopDelay).await.unwrap();
+    assert_eq!(state.detected.sample_rate(), Some(SampRate::Rate48Khz));
+    assert_eq!(hl.mode(), Some(Mode::Active));
+
+    match hl.unmute_when_locked(1, &mut NoopDelay).await {
+        Err(Error::ClockTimeout(state)) => {
+            assert!(state.tdm_clock_error);
+            assert_eq!(state.detected.fs_rate, FsRate::Error);
+            assert_eq!(state.detected.fs_ratio, FsRatio::Invalid);
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn pdm() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x41, 0x27),
+        // SDIN pull downs are left alone
+        read(0x42, 0xc0),
+        reg(0x42, 0xd0),
+        read(0x02, 0x01),
+        reg(0x02, 0x81),
+        read(0x41, 0x27),
+        read(0x42, 0xd0),
+        read(0x02, 0x81),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let config = PdmConfig {
+        clock_mode: PdmMicSlv::SlaveMode,
+        mic1: true,
+        mic2: true,
+        rate: PdmRatePad0::Rate6Mhz114,
+        data_pull_down: true,
+        i2s_mode: true,
+        ..Default::default()
+    };
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.set_pdm(&config).await.unwrap();
+    assert_eq!(hl.pdm().await, Ok(config));
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn asi2() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        reg(0x43, 0xc0),
+        // Fixed sample rate
+        reg(0x44, 0x91),
+        reg(0x45, 0x21),
+        // Second of two devices, with two slots each
+        reg(0x46, 0x15),
+        read(0x43, 0xc0),
+        read(0x44, 0x91),
+        read(0x45, 0x21),
+        read(0x46, 0x15),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let config = Asi2Config::builder()
+        .sbclk_fs_ratio(SbclkFsRatio::Ratio256)
+        .tx_fill(TxFill::TransmitHighZ)
+        .master(true)
+        .tx_edge(TxEdge::FallingEdge)
+        .auto_rate(false)
+        .rx_offset(1)
+        .tx_offset(1)
+        .shared_bus(1, 2)
+        .slots_per_device(2)
+        .build()
+        .unwrap();
+
+    let mut hl = Tas2563::new_i2c(&mut i2c, Address::Global);
+    hl.set_asi2(&config).await.unwrap();
+    assert_eq!(hl.asi2().await, Ok(config));
+
+    i2c.done();
+}
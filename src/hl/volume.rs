@@ -0,0 +1,120 @@
+//! Digital volume of the DSP playback path.
+//!
+//! The volume is a single linear gain in 2.30 format, where 1 corresponds to 0dB, allowing up to
+//! about +6dB. PPC3 tuning programs write it as well, thus set the volume after applying those.
+
+use crate::ll::coefficient::CoefficientAddress;
+use crate::prelude::*;
+
+/// Location of the digital volume coefficient.
+pub const VOLUME_ADDRESS: CoefficientAddress = match CoefficientAddress::new(0x00, 0x02, 0x0c) {
+    Some(address) => address,
+    None => panic!(),
+};
+
+/// Volumes below this level in dB are treated as mute.
+pub const VOLUME_MIN_DB: f32 = -110.0;
+
+/// Highest volume in dB.
+pub const VOLUME_MAX_DB: f32 = 6.0;
+
+/// Coefficient for a volume in dB, clamped to [`VOLUME_MAX_DB`] at the top.
+///
+/// Volumes below [`VOLUME_MIN_DB`] (including negative infinity) and `NaN` mute.
+pub fn volume_from_db(db: f32) -> Q2_30 {
+    if db >= VOLUME_MIN_DB {
+        Q2_30::from_db(db.min(VOLUME_MAX_DB))
+    } else {
+        Q2_30::ZERO
+    }
+}
+
+/// Coefficient for a linear volume, where 1 is 0dB, clamped to [0, 2).
+pub fn volume_from_linear(gain: f32) -> Q2_30 {
+    // Note: `max` maps NaN to zero, the conversion saturates at the top.
+    Q2_30::from_f32(gain.max(0.0))
+}
+
+/// Gradual change of volume, avoiding the zipper noise of large steps.
+#[derive(Debug, Clone)]
+pub struct VolumeRamp {
+    /// Size of each step in dB.
+    pub step_db: f32,
+    /// Time between steps in milliseconds.
+    pub interval_ms: u32,
+}
+
+impl Default for VolumeRamp {
+    fn default() -> Self {
+        Self {
+            step_db: 1.0,
+            interval_ms: 1,
+        }
+    }
+}
+
+impl VolumeRamp {
+    /// Intermediate volumes in dB when going from `from_db` to `to_db`, excluding both.
+    ///
+    /// Mute is ramped to or from [`VOLUME_MIN_DB`].
+    pub(crate) fn steps(&self, from_db: f32, to_db: f32) -> impl Iterator<Item = f32> {
+        let from = clamp_db(from_db);
+        let to = clamp_db(to_db);
+        let step = libm::fabsf(self.step_db).max(0.1);
+        let steps = libm::ceilf(libm::fabsf(to - from) / step) as u32;
+        let step = if to < from { -step } else { step };
+
+        (1..steps).map(move |i| from + step * i as f32)
+    }
+}
+
+fn clamp_db(db: f32) -> f32 {
+    if db >= VOLUME_MIN_DB {
+        db.min(VOLUME_MAX_DB)
+    } else {
+        VOLUME_MIN_DB
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(volume_from_db(0.0), Q2_30(0x4000_0000));
+        assert_eq!(volume_from_db(-6.0206), Q2_30(0x2000_0000));
+        assert_eq!(volume_from_db(12.0), volume_from_db(VOLUME_MAX_DB));
+        assert!(volume_from_db(VOLUME_MAX_DB) < Q2_30::MAX);
+        assert_eq!(volume_from_db(-120.0), Q2_30::ZERO);
+        assert_eq!(volume_from_db(f32::NEG_INFINITY), Q2_30::ZERO);
+        assert_eq!(volume_from_db(f32::NAN), Q2_30::ZERO);
+
+        assert_eq!(volume_from_linear(1.0), Q2_30(0x4000_0000));
+        assert_eq!(volume_from_linear(0.5), Q2_30(0x2000_0000));
+        assert_eq!(volume_from_linear(4.0), Q2_30::MAX);
+        assert_eq!(volume_from_linear(-1.0), Q2_30::ZERO);
+        assert_eq!(volume_from_linear(f32::NAN), Q2_30::ZERO);
+
+        assert!(libm::fabsf(Q2_30(0x4000_0000).to_db()) < 0.001);
+    }
+
+    #[test]
+    fn ramp() {
+        let ramp = VolumeRamp {
+            step_db: 2.0,
+            interval_ms: 1,
+        };
+
+        let mut steps = ramp.steps(-10.0, -4.5);
+        assert_eq!(steps.next(), Some(-8.0));
+        assert_eq!(steps.next(), Some(-6.0));
+        assert_eq!(steps.next(), None);
+
+        assert_eq!(ramp.steps(-4.0, -6.0).count(), 0);
+        assert_eq!(ramp.steps(-4.0, -7.0).collect::<Vec<_>>(), [-6.0]);
+
+        // From mute all the way up.
+        assert_eq!(ramp.steps(f32::NEG_INFINITY, 0.0).count(), 54);
+    }
+}
@@ -3,6 +3,9 @@
 #![allow(async_fn_in_trait)]
 
 pub mod bulk;
+pub mod error;
 pub mod hl;
 pub mod ll;
 pub mod prelude;
+
+pub use error::Error;
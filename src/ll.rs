@@ -1,5 +1,7 @@
 //! Low level interface for the TAS2563 chipset providing register access.
 
+pub mod blocking;
+pub mod coefficient;
 pub mod i2c;
 pub mod spi;
 
@@ -9,6 +11,13 @@ mod test;
 use bitvec::array::BitArray;
 use device_driver::{AddressableDevice, AsyncRegisterDevice};
 
+use crate::Error;
+
+/// Register selecting the page, present on every page.
+pub(crate) const PAGE_REGISTER: u8 = 0x00;
+/// Register selecting the book, only present on page 0.
+pub(crate) const BOOK_REGISTER: u8 = 0x7f;
+
 pub struct Tas2563Device<T> {
     iface: T,
     last_page: Option<u8>,
@@ -32,9 +41,9 @@ impl From<u32> for RegisterAddress {
     }
 }
 
-impl Into<u32> for RegisterAddress {
-    fn into(self) -> u32 {
-        u32::from_be_bytes([0x00, self.book, self.page, self.register])
+impl From<RegisterAddress> for u32 {
+    fn from(value: RegisterAddress) -> Self {
+        u32::from_be_bytes([0x00, value.book, value.page, value.register])
     }
 }
 
@@ -89,27 +98,77 @@ where
     T: Tas2563Interface,
 {
     async fn ensure_book_page(&mut self, address: &RegisterAddress) -> Result<(), T::Error> {
+        if self.last_book != Some(address.book) {
+            // The book can only be selected from page 0.
+            if self.last_page != Some(0x00) {
+                self.iface.write_register(PAGE_REGISTER, 0x00).await?;
+                self.last_page = Some(0x00);
+            }
+            self.iface
+                .write_register(BOOK_REGISTER, address.book)
+                .await?;
+            self.last_book = Some(address.book);
+        }
         if self.last_page != Some(address.page) {
-            self.iface.write_register(0x00, address.page).await?;
+            self.iface
+                .write_register(PAGE_REGISTER, address.page)
+                .await?;
             self.last_page = Some(address.page);
         }
-        if self.last_book != Some(address.book) {
-            self.iface.write_register(0x7f, address.book).await?;
-            self.last_book = Some(address.book);
+        Ok(())
+    }
+
+    /// Write data in burst to the peripheral, bypassing the register map.
+    ///
+    /// The first element in data is the first register address to write to.
+    /// Book and page changes caused by the write are tracked, such that subsequent register
+    /// accesses end up in the right place.
+    pub async fn write_raw(&mut self, data: &[u8]) -> Result<(), Error<T::Error>> {
+        if let Err(e) = self.iface.write_burst(data).await {
+            // The write might have partially succeeded.
+            self.reset_assumptions();
+            return Err(e.into());
         }
+        self.track_raw_write(data);
         Ok(())
     }
+}
 
+impl<T> Tas2563Device<T> {
     pub fn interface(&mut self) -> &mut T {
         &mut self.iface
     }
+
+    pub fn reset_assumptions(&mut self) {
+        self.last_book = None;
+        self.last_page = None;
+    }
+
+    /// Update the book and page assumptions according to a raw burst write.
+    fn track_raw_write(&mut self, data: &[u8]) {
+        let Some((&register, values)) = data.split_first() else {
+            return;
+        };
+
+        for (register, value) in (register..=0xff).zip(values) {
+            match register {
+                PAGE_REGISTER => self.last_page = Some(*value),
+                BOOK_REGISTER => match self.last_page {
+                    Some(0x00) => self.last_book = Some(*value),
+                    Some(_) => {} // Regular memory outside of page 0
+                    None => self.last_book = None,
+                },
+                _ => {}
+            }
+        }
+    }
 }
 
 impl<T> AsyncRegisterDevice for Tas2563Device<T>
 where
     T: Tas2563Interface,
 {
-    type Error = T::Error;
+    type Error = Error<T::Error>;
 
     async fn write_register<const SIZE_BYTES: usize>(
         &mut self,
@@ -119,9 +178,10 @@ where
         let address = RegisterAddress::from(address);
         self.ensure_book_page(&address).await?;
 
-        self.iface
+        Ok(self
+            .iface
             .write_registers(address.register, data.as_raw_slice())
-            .await
+            .await?)
     }
 
     async fn read_register<const SIZE_BYTES: usize>(
@@ -132,19 +192,10 @@ where
         let address = RegisterAddress::from(address);
         self.ensure_book_page(&address).await?;
 
-        self.iface
+        Ok(self
+            .iface
             .read_registers(address.register, data.as_raw_mut_slice())
-            .await
-    }
-}
-
-impl<T> Tas2563Device<T>
-where
-    Self: AsyncRegisterDevice + AddressableDevice<AddressType = u32>,
-{
-    pub fn reset_assumptions(&mut self) {
-        self.last_book = None;
-        self.last_page = None;
+            .await?)
     }
 }
 
@@ -0,0 +1,116 @@
+//! Blocking counterpart of the low level interface, for use without an async executor.
+
+pub mod i2c;
+pub mod spi;
+
+use bitvec::array::BitArray;
+use device_driver::RegisterDevice;
+
+use super::{RegisterAddress, Tas2563Device, BOOK_REGISTER, PAGE_REGISTER};
+use crate::Error;
+
+pub trait Tas2563Interface {
+    type Error;
+
+    /// Write data in burst to the peripheral.
+    ///
+    /// The first element in data is the first register address to write to.
+    /// If the interface does not support burst write, it is required to unwrap the burst write into
+    /// separate single register writes.
+    fn write_burst(&mut self, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Read a series of register values from the peripheral.
+    ///
+    /// If the interface does not support multibyte read, it is required to unwrap the read into
+    /// separate single register reads.
+    fn read_registers(&mut self, register: u8, values: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Convenience function to read a single register value.
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut buf = [0u8];
+        self.read_registers(register, &mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Convenience function to create a single register write.
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.write_burst(&[register, value])
+    }
+
+    /// Convenience function to create a multi register write.
+    fn write_registers(&mut self, register: u8, values: &[u8]) -> Result<(), Self::Error> {
+        for (register, v) in (register..).zip(values) {
+            self.write_register(register, *v)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> Tas2563Device<T>
+where
+    T: Tas2563Interface,
+{
+    pub(super) fn ensure_book_page_blocking(
+        &mut self,
+        address: &RegisterAddress,
+    ) -> Result<(), T::Error> {
+        if self.last_book != Some(address.book) {
+            // The book can only be selected from page 0.
+            if self.last_page != Some(0x00) {
+                self.iface.write_register(PAGE_REGISTER, 0x00)?;
+                self.last_page = Some(0x00);
+            }
+            self.iface.write_register(BOOK_REGISTER, address.book)?;
+            self.last_book = Some(address.book);
+        }
+        if self.last_page != Some(address.page) {
+            self.iface.write_register(PAGE_REGISTER, address.page)?;
+            self.last_page = Some(address.page);
+        }
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`Tas2563Device::write_raw`].
+    pub fn write_raw_blocking(&mut self, data: &[u8]) -> Result<(), Error<T::Error>> {
+        if let Err(e) = self.iface.write_burst(data) {
+            // The write might have partially succeeded.
+            self.reset_assumptions();
+            return Err(e.into());
+        }
+        self.track_raw_write(data);
+        Ok(())
+    }
+}
+
+impl<T> RegisterDevice for Tas2563Device<T>
+where
+    T: Tas2563Interface,
+{
+    type Error = Error<T::Error>;
+
+    fn write_register<const SIZE_BYTES: usize>(
+        &mut self,
+        address: Self::AddressType,
+        data: &BitArray<[u8; SIZE_BYTES]>,
+    ) -> Result<(), Self::Error> {
+        let address = RegisterAddress::from(address);
+        self.ensure_book_page_blocking(&address)?;
+
+        Ok(self
+            .iface
+            .write_registers(address.register, data.as_raw_slice())?)
+    }
+
+    fn read_register<const SIZE_BYTES: usize>(
+        &mut self,
+        address: Self::AddressType,
+        data: &mut BitArray<[u8; SIZE_BYTES]>,
+    ) -> Result<(), Self::Error> {
+        let address = RegisterAddress::from(address);
+        self.ensure_book_page_blocking(&address)?;
+
+        Ok(self
+            .iface
+            .read_registers(address.register, data.as_raw_mut_slice())?)
+    }
+}
@@ -0,0 +1,38 @@
+use embedded_hal::i2c::I2c;
+
+use super::Tas2563Interface;
+use crate::ll::{i2c::Address, Tas2563Device};
+
+pub struct I2CInterface<T: I2c> {
+    address: Address,
+    i2c: T,
+}
+
+impl<T: I2c> Tas2563Interface for I2CInterface<T> {
+    type Error = T::Error;
+
+    fn write_burst(&mut self, data: &[u8]) -> Result<(), T::Error> {
+        self.i2c.write(self.address as u8, data)
+    }
+
+    fn read_registers(&mut self, register: u8, values: &mut [u8]) -> Result<(), T::Error> {
+        self.i2c.write_read(self.address as u8, &[register], values)
+    }
+}
+
+impl<T> Tas2563Device<I2CInterface<T>>
+where
+    T: I2c,
+{
+    pub fn new_blocking_i2c(i2c: T, address: Address) -> Self {
+        Self {
+            iface: I2CInterface { i2c, address },
+            last_page: None,
+            last_book: None,
+        }
+    }
+
+    pub fn take(self) -> T {
+        self.iface.i2c
+    }
+}
@@ -0,0 +1,56 @@
+use embedded_hal::spi::SpiDevice;
+
+use super::Tas2563Interface;
+use crate::ll::Tas2563Device;
+
+pub struct SPIInterface<T: SpiDevice> {
+    spi: T,
+}
+
+impl<T: SpiDevice> Tas2563Interface for SPIInterface<T> {
+    type Error = T::Error;
+
+    fn write_burst(&mut self, data: &[u8]) -> Result<(), T::Error> {
+        let Some((&register, values)) = data.split_first() else {
+            return Ok(());
+        };
+        for (register, b) in (register..=0xff).zip(values) {
+            self.write_register(register, *b)?;
+        }
+        Ok(())
+    }
+
+    fn read_registers(&mut self, register: u8, data: &mut [u8]) -> Result<(), T::Error> {
+        for (register, b) in (register..=0xff).zip(data.iter_mut()) {
+            *b = self.read_register(register)?;
+        }
+        Ok(())
+    }
+
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut buf = [register << 1 | 0b1, 0x00];
+        self.spi.transfer_in_place(&mut buf)?;
+        Ok(buf[1])
+    }
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.spi.write(&[register << 1, value])
+    }
+}
+
+impl<T> Tas2563Device<SPIInterface<T>>
+where
+    T: SpiDevice,
+{
+    pub fn new_blocking_spi(spi: T) -> Self {
+        Self {
+            iface: SPIInterface { spi },
+            last_page: None,
+            last_book: None,
+        }
+    }
+
+    pub fn take(self) -> T {
+        self.iface.spi
+    }
+}
@@ -0,0 +1,265 @@
+//! Access to the DSP coefficient memory in books other than the register map.
+//!
+//! Coefficients are 32 bit big endian words, stored from register 0x08 up to and including 0x7f
+//! of each page. A block of coefficients continues at register 0x08 of the next page.
+
+use core::ops::Range;
+
+use super::{blocking, RegisterAddress, Tas2563Device, Tas2563Interface};
+use crate::Error;
+
+/// First register of a page holding coefficient memory.
+const FIRST_REGISTER: u8 = 0x08;
+/// Amount of coefficients that fit in a single page.
+pub const WORDS_PER_PAGE: usize = 30;
+
+/// Location of a single coefficient word.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CoefficientAddress {
+    book: u8,
+    page: u8,
+    register: u8,
+}
+
+impl CoefficientAddress {
+    /// Address of the coefficient starting at `register`.
+    ///
+    /// Returns `None` if the register is not the start of a coefficient word.
+    pub const fn new(book: u8, page: u8, register: u8) -> Option<Self> {
+        if register < FIRST_REGISTER || register > 0x7c || !register.is_multiple_of(4) {
+            return None;
+        }
+        Some(Self {
+            book,
+            page,
+            register,
+        })
+    }
+
+    pub fn book(&self) -> u8 {
+        self.book
+    }
+
+    pub fn page(&self) -> u8 {
+        self.page
+    }
+
+    pub fn register(&self) -> u8 {
+        self.register
+    }
+
+    /// Index of the word within its page.
+    fn index(&self) -> usize {
+        (self.register - FIRST_REGISTER) as usize / 4
+    }
+
+    fn as_register_address(&self) -> RegisterAddress {
+        RegisterAddress {
+            book: self.book,
+            page: self.page,
+            register: self.register,
+        }
+    }
+}
+
+/// Split a block of `len` words starting at `address` in chunks that do not cross a page.
+///
+/// Returns `None` if the block runs past the last page of the book.
+fn chunks(
+    address: CoefficientAddress,
+    len: usize,
+) -> Option<impl Iterator<Item = (CoefficientAddress, Range<usize>)>> {
+    let last_page =
+        address.page as usize + (address.index() + len).saturating_sub(1) / WORDS_PER_PAGE;
+    if last_page > u8::MAX as usize {
+        return None;
+    }
+
+    let mut address = address;
+    let mut start = 0;
+    Some(core::iter::from_fn(move || {
+        if start >= len {
+            return None;
+        }
+        let count = (WORDS_PER_PAGE - address.index()).min(len - start);
+        let chunk = (address, start..start + count);
+        start += count;
+        if start < len {
+            address = CoefficientAddress {
+                book: address.book,
+                page: address.page + 1,
+                register: FIRST_REGISTER,
+            };
+        }
+        Some(chunk)
+    }))
+}
+
+impl<T> Tas2563Device<T>
+where
+    T: Tas2563Interface,
+{
+    /// Write a block of coefficient words, continuing on the next page when required.
+    pub async fn write_coefficients(
+        &mut self,
+        address: CoefficientAddress,
+        words: &[i32],
+    ) -> Result<(), Error<T::Error>> {
+        let chunks = chunks(address, words.len()).ok_or(Error::CoefficientOutOfRange)?;
+        for (address, range) in chunks {
+            let mut buf = [0u8; 1 + WORDS_PER_PAGE * 4];
+            buf[0] = address.register;
+            for (dest, word) in buf[1..].chunks_exact_mut(4).zip(&words[range.clone()]) {
+                dest.copy_from_slice(&word.to_be_bytes());
+            }
+
+            self.ensure_book_page(&address.as_register_address())
+                .await?;
+            self.iface.write_burst(&buf[..1 + range.len() * 4]).await?;
+        }
+        Ok(())
+    }
+
+    /// Read a block of coefficient words, continuing on the next page when required.
+    pub async fn read_coefficients(
+        &mut self,
+        address: CoefficientAddress,
+        words: &mut [i32],
+    ) -> Result<(), Error<T::Error>> {
+        let chunks = chunks(address, words.len()).ok_or(Error::CoefficientOutOfRange)?;
+        for (address, range) in chunks {
+            let mut buf = [0u8; WORDS_PER_PAGE * 4];
+            let buf = &mut buf[..range.len() * 4];
+
+            self.ensure_book_page(&address.as_register_address())
+                .await?;
+            self.iface.read_registers(address.register, buf).await?;
+
+            for (word, src) in words[range].iter_mut().zip(buf.chunks_exact(4)) {
+                *word = i32::from_be_bytes([src[0], src[1], src[2], src[3]]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a single coefficient word.
+    pub async fn write_coefficient(
+        &mut self,
+        address: CoefficientAddress,
+        word: i32,
+    ) -> Result<(), Error<T::Error>> {
+        self.write_coefficients(address, &[word]).await
+    }
+
+    /// Read a single coefficient word.
+    pub async fn read_coefficient(
+        &mut self,
+        address: CoefficientAddress,
+    ) -> Result<i32, Error<T::Error>> {
+        let mut word = [0i32];
+        self.read_coefficients(address, &mut word).await?;
+        Ok(word[0])
+    }
+}
+
+impl<T> Tas2563Device<T>
+where
+    T: blocking::Tas2563Interface,
+{
+    /// Blocking counterpart of [`Tas2563Device::write_coefficients`].
+    pub fn write_coefficients_blocking(
+        &mut self,
+        address: CoefficientAddress,
+        words: &[i32],
+    ) -> Result<(), Error<T::Error>> {
+        let chunks = chunks(address, words.len()).ok_or(Error::CoefficientOutOfRange)?;
+        for (address, range) in chunks {
+            let mut buf = [0u8; 1 + WORDS_PER_PAGE * 4];
+            buf[0] = address.register;
+            for (dest, word) in buf[1..].chunks_exact_mut(4).zip(&words[range.clone()]) {
+                dest.copy_from_slice(&word.to_be_bytes());
+            }
+
+            self.ensure_book_page_blocking(&address.as_register_address())?;
+            self.iface.write_burst(&buf[..1 + range.len() * 4])?;
+        }
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`Tas2563Device::read_coefficients`].
+    pub fn read_coefficients_blocking(
+        &mut self,
+        address: CoefficientAddress,
+        words: &mut [i32],
+    ) -> Result<(), Error<T::Error>> {
+        let chunks = chunks(address, words.len()).ok_or(Error::CoefficientOutOfRange)?;
+        for (address, range) in chunks {
+            let mut buf = [0u8; WORDS_PER_PAGE * 4];
+            let buf = &mut buf[..range.len() * 4];
+
+            self.ensure_book_page_blocking(&address.as_register_address())?;
+            self.iface.read_registers(address.register, buf)?;
+
+            for (word, src) in words[range].iter_mut().zip(buf.chunks_exact(4)) {
+                *word = i32::from_be_bytes([src[0], src[1], src[2], src[3]]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Blocking counterpart of [`Tas2563Device::write_coefficient`].
+    pub fn write_coefficient_blocking(
+        &mut self,
+        address: CoefficientAddress,
+        word: i32,
+    ) -> Result<(), Error<T::Error>> {
+        self.write_coefficients_blocking(address, &[word])
+    }
+
+    /// Blocking counterpart of [`Tas2563Device::read_coefficient`].
+    pub fn read_coefficient_blocking(
+        &mut self,
+        address: CoefficientAddress,
+    ) -> Result<i32, Error<T::Error>> {
+        let mut word = [0i32];
+        self.read_coefficients_blocking(address, &mut word)?;
+        Ok(word[0])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{chunks, CoefficientAddress};
+
+    #[test]
+    fn address() {
+        assert!(CoefficientAddress::new(0x8c, 0x01, 0x04).is_none());
+        assert!(CoefficientAddress::new(0x8c, 0x01, 0x0a).is_none());
+        assert!(CoefficientAddress::new(0x8c, 0x01, 0x80).is_none());
+        assert!(CoefficientAddress::new(0x8c, 0x01, 0x08).is_some());
+        assert!(CoefficientAddress::new(0x8c, 0x01, 0x7c).is_some());
+    }
+
+    #[test]
+    fn chunks_across_pages() {
+        let address = CoefficientAddress::new(0x8c, 0x01, 0x78).unwrap();
+        let result: Vec<_> = chunks(address, 33).unwrap().collect();
+
+        assert_eq!(
+            result,
+            [
+                (address, 0..2),
+                (CoefficientAddress::new(0x8c, 0x02, 0x08).unwrap(), 2..32),
+                (CoefficientAddress::new(0x8c, 0x03, 0x08).unwrap(), 32..33),
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_out_of_range() {
+        let address = CoefficientAddress::new(0x8c, 0xff, 0x7c).unwrap();
+        assert!(chunks(address, 1).is_some());
+        assert!(chunks(address, 2).is_none());
+    }
+}
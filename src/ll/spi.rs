@@ -10,16 +10,17 @@ impl<T: SpiDevice> Tas2563Interface for SPIInterface<T> {
     type Error = T::Error;
 
     async fn write_burst(&mut self, data: &[u8]) -> Result<(), T::Error> {
-        let mut register = data[0];
-        for b in &data[1..] {
+        let Some((&register, values)) = data.split_first() else {
+            return Ok(());
+        };
+        for (register, b) in (register..=0xff).zip(values) {
             self.write_register(register, *b).await?;
-            register += 1;
         }
         Ok(())
     }
 
     async fn read_registers(&mut self, register: u8, data: &mut [u8]) -> Result<(), T::Error> {
-        for (register, b) in (register..(register + data.len() as u8)).zip(data.iter_mut()) {
+        for (register, b) in (register..=0xff).zip(data.iter_mut()) {
             *b = self.read_register(register).await?;
         }
         Ok(())
@@ -1,6 +1,12 @@
-use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
-
-use super::{i2c::Address, Tas2563Device};
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay,
+    i2c::{Mock, Transaction},
+    spi,
+};
+
+use super::{
+    blocking, coefficient::CoefficientAddress, i2c::Address, Tas2563Device, Tas2563Interface,
+};
 use crate::prelude::*;
 
 fn reg(register: u8, value: u8) -> Transaction {
@@ -285,7 +291,7 @@ async fn example() {
     ll.clock_configuration()
         .write_async(|w| {
             w.auto_clk(false)
-                .sbclk_fs_ratio(0b1000)
+                .sbclk_fs_ratio(SbclkFsRatio::Ratio256)
                 .sel_madc_div_rev(0x0)
                 .inv_dac_out_phase(false)
         })
@@ -320,3 +326,159 @@ async fn example() {
 
     i2c.done();
 }
+
+#[test]
+fn blocking() {
+    let expectations = [
+        // Set page to 0x00
+        reg(0x00, 0x00),
+        // Set book to 0x00
+        reg(0x7f, 0x00),
+        // Software reset
+        reg(0x01, 0x01),
+        // Mute and power sensing up
+        reg(0x02, 0x01),
+        // Read back revision
+        Transaction::write_read(Address::Global as u8, vec![0x7d], vec![0x21]),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut ll = Tas2563Device::new_blocking_i2c(&mut i2c, Address::Global);
+    ll.software_reset()
+        .write(|w| w.software_reset(true))
+        .unwrap();
+
+    ll.pwr_ctl().write(|w| w.mode(Mode::Mute)).unwrap();
+
+    let rev_id = ll.rev_id().read().unwrap();
+    assert_eq!(rev_id.rev_id(), 0x2);
+    assert_eq!(rev_id.pg_id(), 0x1);
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn bulk_tracks_book_page() {
+    let expectations = [
+        // Blob: select page 0, book 0x8c, page 5 and write a coefficient
+        reg(0x00, 0x00),
+        reg(0x7f, 0x8c),
+        reg(0x00, 0x05),
+        Transaction::write(Address::Global as u8, vec![0x7c, 0x01, 0x02, 0x03, 0x04]),
+        // Return to book 0 via page 0
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        // Mute
+        reg(0x02, 0x01),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut ll = Tas2563Device::new_i2c(&mut i2c, Address::Global);
+    crate::bulk::CommandIterator::new(&[
+        0x00, 0x00, 0x7f, 0x8c, 0x00, 0x05, 0xfd, 0x04, 0x7c, 0x01, 0x02, 0x03, 0x04, 0x00,
+    ])
+    .write(&mut ll, &mut NoopDelay)
+    .await
+    .unwrap();
+
+    ll.pwr_ctl()
+        .write_async(|w| w.mode(Mode::Mute))
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn raw_write_up_to_last_register() {
+    let expectations = [
+        reg(0x00, 0x00),
+        reg(0x7f, 0x00),
+        Transaction::write(Address::Global as u8, vec![0xfe, 0x01, 0x02]),
+        reg(0xff, 0x03),
+        // Book and page are still known
+        reg(0x02, 0x01),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut ll = Tas2563Device::new_i2c(&mut i2c, Address::Global);
+    ll.write_raw(&[0x00, 0x00]).await.unwrap();
+    ll.write_raw(&[0x7f, 0x00]).await.unwrap();
+    ll.write_raw(&[0xfe, 0x01, 0x02]).await.unwrap();
+    ll.write_raw(&[0xff, 0x03]).await.unwrap();
+
+    ll.pwr_ctl()
+        .write_async(|w| w.mode(Mode::Mute))
+        .await
+        .unwrap();
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn coefficients() {
+    let expectations = [
+        // Select book 0x8c via page 0
+        reg(0x00, 0x00),
+        reg(0x7f, 0x8c),
+        // Last word of page 1
+        reg(0x00, 0x01),
+        Transaction::write(Address::Global as u8, vec![0x7c, 0x40, 0x00, 0x00, 0x00]),
+        // First word of page 2
+        reg(0x00, 0x02),
+        Transaction::write(Address::Global as u8, vec![0x08, 0xff, 0xff, 0xff, 0xfe]),
+        // Read back without touching book or page
+        Transaction::write_read(
+            Address::Global as u8,
+            vec![0x08],
+            vec![0xff, 0xff, 0xff, 0xfe],
+        ),
+    ];
+    let mut i2c = Mock::new(&expectations);
+
+    let mut ll = Tas2563Device::new_i2c(&mut i2c, Address::Global);
+    ll.write_coefficients(
+        CoefficientAddress::new(0x8c, 0x01, 0x7c).unwrap(),
+        &[0x4000_0000, -2],
+    )
+    .await
+    .unwrap();
+
+    let word = ll
+        .read_coefficient(CoefficientAddress::new(0x8c, 0x02, 0x08).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(word, -2);
+
+    i2c.done();
+}
+
+#[async_std::test]
+async fn spi_burst_up_to_last_register() {
+    let write = |data: Vec<u8>| {
+        [
+            spi::Transaction::transaction_start(),
+            spi::Transaction::write_vec(data),
+            spi::Transaction::transaction_end(),
+        ]
+    };
+    let expectations: Vec<_> = [write(vec![0xfc, 0x01]), write(vec![0xfe, 0x02])]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let mut spi = spi::Mock::new(&expectations);
+    let mut ll = Tas2563Device::new_spi(&mut spi);
+    ll.interface().write_burst(&[]).await.unwrap();
+    ll.interface()
+        .write_burst(&[0xfe, 0x01, 0x02])
+        .await
+        .unwrap();
+    spi.done();
+
+    let mut spi = spi::Mock::new(&expectations);
+    let mut ll = Tas2563Device::new_blocking_spi(&mut spi);
+    blocking::Tas2563Interface::write_burst(ll.interface(), &[]).unwrap();
+    blocking::Tas2563Interface::write_burst(ll.interface(), &[0xfe, 0x01, 0x02]).unwrap();
+    spi.done();
+}
@@ -1,14 +1,15 @@
 //! Strongly typed register values used by this crate.
 pub use crate::ll::registers::{
-    AmpLevel, BopAtkRt, BopAtkSt, BopHldTm, BstClassHStepTime, BstIr, BstLr, BstMode, BstPa,
-    BstPfml, BstVreg, FrameStart, IrqzPinCfg, IrqzPol, IvmonLen, LimbAtkRt, LimbAtkSt, LimbHldTm,
-    LimbRlsRt, LimbRlsSt, Mode, RxScfg, RxSlen, RxWlen, SampRate, Tg1En, Tg1Pinen, TxEdge, TxFill,
-    VbatLimThSelection,
+    AmpLevel, Asi2SbclkMaster, BopAtkRt, BopAtkSt, BopHldTm, BstClassHStepTime, BstIr, BstLr,
+    BstMode, BstPa, BstPfml, BstVreg, FrameStart, FsRate, FsRatio, IrqzPinCfg, IrqzPol, IvmonLen,
+    LimbAtkRt, LimbAtkSt, LimbHldTm, LimbRlsRt, LimbRlsSt, Mode, PdmMicSlv, PdmPad0CapEdge,
+    PdmRatePad0, RxEdge, RxJustify, RxScfg, RxSlen, RxWlen, SampRate, SbclkFsRatio, Tg1En,
+    Tg1Pinen, TxEdge, TxFill, VbatLimThSelection, VbatSlen,
 };
 
 use derive_more::{From, Into};
 
-#[derive(From, Into, Debug, PartialEq)]
+#[derive(From, Into, Debug, PartialEq, Clone, Copy)]
 pub struct BoostPeakCurrentMaxRun(pub u8);
 
 impl BoostPeakCurrentMaxRun {
@@ -22,6 +23,15 @@ impl BoostPeakCurrentMaxRun {
             BoostPeakCurrentMaxRun(i as u8)
         }
     }
+
+    /// Peak current limit, codes above 4A saturate.
+    pub fn to_milliamps(self) -> u16 {
+        if self.0 >= 0x37 {
+            4000
+        } else {
+            990 + self.0 as u16 * 55
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -33,9 +43,9 @@ impl From<u16> for VBatCnv {
     }
 }
 
-impl Into<u16> for VBatCnv {
-    fn into(self) -> u16 {
-        self.0 << 6
+impl From<VBatCnv> for u16 {
+    fn from(value: VBatCnv) -> Self {
+        value.0 << 6
     }
 }
 
@@ -54,9 +64,9 @@ impl From<u16> for PVDDCnv {
     }
 }
 
-impl Into<u16> for PVDDCnv {
-    fn into(self) -> u16 {
-        self.0 << 6
+impl From<PVDDCnv> for u16 {
+    fn from(value: PVDDCnv) -> Self {
+        value.0 << 6
     }
 }
 
@@ -99,9 +109,191 @@ impl From<ADCReadout> for ADCReadOutReadable {
     }
 }
 
+/// Voltage in millivolts.
+#[derive(From, Into, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Millivolts(pub u16);
+
+/// Temperature in degrees celsius.
+#[derive(From, Into, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Celsius(pub i16);
+
+impl From<VBatCnv> for Millivolts {
+    fn from(value: VBatCnv) -> Self {
+        Self(value.to_millivolts())
+    }
+}
+
+impl From<PVDDCnv> for Millivolts {
+    fn from(value: PVDDCnv) -> Self {
+        Self(value.to_millivolts())
+    }
+}
+
+impl From<TempCnv> for Celsius {
+    fn from(value: TempCnv) -> Self {
+        Self(value.to_celcius())
+    }
+}
+
+impl AmpLevel {
+    /// Lowest analog gain in dBV.
+    pub const MIN_DBV: f32 = 8.5;
+    /// Highest analog gain in dBV.
+    pub const MAX_DBV: f32 = 21.5;
+    /// Distance between two levels in dB.
+    pub const STEP_DB: f32 = 0.5;
+
+    /// Level nearest to `dbv`.
+    ///
+    /// Values half-way between two levels round down to the quieter level, values outside of
+    /// [`AmpLevel::MIN_DBV`, `AmpLevel::MAX_DBV`] are clamped and `NaN` yields the lowest level.
+    pub fn from_dbv(dbv: f32) -> Self {
+        Self::from_steps(libm::ceilf((dbv - Self::MIN_DBV) / Self::STEP_DB - 0.5))
+    }
+
+    /// Gain in dBV, or `None` for [`AmpLevel::Unknown`].
+    pub fn to_dbv(self) -> Option<f32> {
+        match self {
+            AmpLevel::Unknown => None,
+            level => Some(Self::MIN_DBV + (u8::from(level) - 1) as f32 * Self::STEP_DB),
+        }
+    }
+
+    /// Highest level at which a full scale sine does not clip on an output supply of `volts`.
+    ///
+    /// The output stage is supplied by the boost converter, or by VBAT when the boost converter
+    /// is disabled. Returns the lowest level for supplies too low for any level.
+    pub fn max_for_supply(volts: f32) -> Self {
+        // Peak voltage of a full scale sine is sqrt(2) times its RMS voltage.
+        let dbv = 20.0 * libm::log10f(volts / core::f32::consts::SQRT_2);
+        Self::from_steps(libm::floorf((dbv - Self::MIN_DBV) / Self::STEP_DB))
+    }
+
+    fn from_steps(steps: f32) -> Self {
+        let max = (Self::MAX_DBV - Self::MIN_DBV) / Self::STEP_DB;
+        // NaN saturates to 0 in the cast.
+        Self::from(steps.clamp(0.0, max) as u8 + 1)
+    }
+}
+
+impl BstVreg {
+    /// Regulated boost voltage.
+    pub fn to_volts(self) -> f32 {
+        5.5 + u8::from(self) as f32 * 0.5
+    }
+}
+
+/// Formats register values by their raw value, as the generated types do not derive
+/// `defmt::Format`.
+macro_rules! format_raw {
+    ($($name:ident),*) => {
+        $(
+            #[cfg(feature = "defmt")]
+            impl defmt::Format for $name {
+                fn format(&self, f: defmt::Formatter) {
+                    defmt::write!(f, "{=str}({=u8:#x})", stringify!($name), u8::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+format_raw!(FsRate, FsRatio, SampRate, SbclkFsRatio);
+
+/// Defines a signed fixed point coefficient type with `$frac` fractional bits, as used by the DSP.
+macro_rules! fixed_point {
+    ($(#[$meta:meta])* $name:ident, $frac:literal) => {
+        $(#[$meta])*
+        ///
+        /// Conversions from floating point round to the nearest representable value and saturate
+        /// at the limits of the format, `NaN` converts to zero.
+        #[allow(non_camel_case_types)]
+        #[derive(From, Into, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        pub struct $name(pub i32);
+
+        impl $name {
+            /// Amount of fractional bits.
+            pub const FRACTIONAL_BITS: u32 = $frac;
+            pub const MIN: Self = Self(i32::MIN);
+            pub const MAX: Self = Self(i32::MAX);
+            pub const ZERO: Self = Self(0);
+
+            pub fn from_f32(value: f32) -> Self {
+                let scaled = libm::round(value as f64 * (1u64 << $frac) as f64);
+                // Note: float to integer casts saturate, and map NaN to zero.
+                Self(scaled as i32)
+            }
+
+            pub fn to_f32(self) -> f32 {
+                (self.0 as f64 / (1u64 << $frac) as f64) as f32
+            }
+
+            /// Linear gain corresponding to a gain in dB.
+            pub fn from_db(db: f32) -> Self {
+                Self::from_f32(libm::powf(10.0, db / 20.0))
+            }
+
+            /// Gain in dB of the magnitude of this linear gain.
+            ///
+            /// Zero results in negative infinity.
+            pub fn to_db(self) -> f32 {
+                20.0 * libm::log10f(libm::fabsf(self.to_f32()))
+            }
+
+            pub fn from_be_bytes(bytes: [u8; 4]) -> Self {
+                Self(i32::from_be_bytes(bytes))
+            }
+
+            pub fn to_be_bytes(self) -> [u8; 4] {
+                self.0.to_be_bytes()
+            }
+
+            pub fn saturating_add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0))
+            }
+
+            pub fn saturating_sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+
+            pub fn saturating_neg(self) -> Self {
+                Self(self.0.saturating_neg())
+            }
+
+            pub fn saturating_mul(self, rhs: Self) -> Self {
+                let product = (self.0 as i64 * rhs.0 as i64 + (1 << ($frac - 1))) >> $frac;
+                Self(product.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+            }
+        }
+    };
+}
+
+fixed_point!(
+    /// DSP coefficient in 1.31 format, in the range [-1, 1). Mostly used for filter coefficients.
+    Q1_31,
+    31
+);
+
+fixed_point!(
+    /// DSP coefficient in 2.30 format, in the range [-2, 2). Used for the digital volume.
+    Q2_30,
+    30
+);
+
+fixed_point!(
+    /// DSP coefficient in 5.27 format, in the range [-16, 16). Mostly used for gains and thresholds.
+    Q5_27,
+    27
+);
+
 #[cfg(test)]
 mod test {
-    use crate::prelude::{BoostPeakCurrentMaxRun, TempCnv, VBatCnv};
+    use crate::prelude::{
+        AmpLevel, BoostPeakCurrentMaxRun, BstVreg, TempCnv, VBatCnv, Q1_31, Q2_30, Q5_27,
+    };
 
     #[test]
     fn boost_peak_current_max_run() {
@@ -117,6 +309,10 @@ mod test {
             BoostPeakCurrentMaxRun::from_milliamps(4000),
             BoostPeakCurrentMaxRun(0x37)
         );
+
+        assert_eq!(BoostPeakCurrentMaxRun(0).to_milliamps(), 990);
+        assert_eq!(BoostPeakCurrentMaxRun(0x36).to_milliamps(), 3960);
+        assert_eq!(BoostPeakCurrentMaxRun(0x3f).to_milliamps(), 4000);
     }
 
     #[test]
@@ -133,4 +329,84 @@ mod test {
         assert_eq!(TempCnv(0x76).to_celcius(), 25);
         assert_eq!(TempCnv(0xFF).to_celcius(), 162);
     }
+
+    #[test]
+    fn fixed_point_f32() {
+        assert_eq!(Q5_27::from_f32(1.0), Q5_27(0x0800_0000));
+        assert_eq!(Q5_27::from_f32(-0.5), Q5_27(-0x0400_0000));
+        assert_eq!(Q5_27(0x0800_0000).to_f32(), 1.0);
+        assert_eq!(Q1_31::from_f32(0.5), Q1_31(0x4000_0000));
+        assert_eq!(Q2_30::from_f32(1.0), Q2_30(0x4000_0000));
+
+        // Saturation
+        assert_eq!(Q1_31::from_f32(1.0), Q1_31::MAX);
+        assert_eq!(Q1_31::from_f32(-2.0), Q1_31::MIN);
+        assert_eq!(Q5_27::from_f32(100.0), Q5_27::MAX);
+        assert_eq!(Q5_27::from_f32(f32::NAN), Q5_27::ZERO);
+    }
+
+    #[test]
+    fn fixed_point_db() {
+        assert_eq!(Q5_27::from_db(0.0), Q5_27(0x0800_0000));
+        assert_eq!(Q5_27::from_db(-6.0206), Q5_27::from_f32(0.5));
+        assert!((Q5_27::from_f32(2.0).to_db() - 6.0206).abs() < 0.001);
+        assert!((Q1_31::from_f32(-0.1).to_db() + 20.0).abs() < 0.001);
+        assert_eq!(Q5_27::ZERO.to_db(), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn fixed_point_arithmetic() {
+        let half = Q5_27::from_f32(0.5);
+        let two = Q5_27::from_f32(2.0);
+        assert_eq!(half.saturating_mul(two), Q5_27::from_f32(1.0));
+        assert_eq!(two.saturating_mul(two), Q5_27::from_f32(4.0));
+        assert_eq!(Q5_27::from_f32(15.0).saturating_mul(two), Q5_27::MAX);
+        assert_eq!(Q5_27::MAX.saturating_add(two), Q5_27::MAX);
+        assert_eq!(Q5_27::MIN.saturating_sub(two), Q5_27::MIN);
+        assert_eq!(Q1_31::MIN.saturating_neg(), Q1_31::MAX);
+        assert_eq!(
+            Q1_31::from_f32(-0.5).saturating_mul(Q1_31::from_f32(-0.5)),
+            Q1_31::from_f32(0.25)
+        );
+    }
+
+    #[test]
+    fn fixed_point_bytes() {
+        assert_eq!(Q5_27::from_f32(1.0).to_be_bytes(), [0x08, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            Q1_31::from_be_bytes([0xc0, 0x00, 0x00, 0x00]),
+            Q1_31::from_f32(-0.5)
+        );
+    }
+
+    #[test]
+    fn amp_level_dbv() {
+        assert_eq!(AmpLevel::from_dbv(8.5), AmpLevel::Amp8DBv5);
+        assert_eq!(AmpLevel::from_dbv(16.2), AmpLevel::Amp16DBv0);
+        assert_eq!(AmpLevel::from_dbv(16.25), AmpLevel::Amp16DBv0);
+        assert_eq!(AmpLevel::from_dbv(16.3), AmpLevel::Amp16DBv5);
+        assert_eq!(AmpLevel::from_dbv(-3.0), AmpLevel::Amp8DBv5);
+        assert_eq!(AmpLevel::from_dbv(30.0), AmpLevel::Amp21DBv5);
+        assert_eq!(AmpLevel::from_dbv(f32::NAN), AmpLevel::Amp8DBv5);
+
+        assert_eq!(AmpLevel::Amp8DBv5.to_dbv(), Some(8.5));
+        assert_eq!(AmpLevel::Amp21DBv5.to_dbv(), Some(21.5));
+        assert_eq!(AmpLevel::Unknown.to_dbv(), None);
+    }
+
+    #[test]
+    fn amp_level_max_for_supply() {
+        // 11V peak is 7.78V RMS or 17.8dBV.
+        assert_eq!(
+            AmpLevel::max_for_supply(BstVreg::Boost11V0.to_volts()),
+            AmpLevel::Amp17DBv5
+        );
+        assert_eq!(
+            AmpLevel::max_for_supply(BstVreg::Boost8V5.to_volts()),
+            AmpLevel::Amp15DBv5
+        );
+        assert_eq!(AmpLevel::max_for_supply(3.6), AmpLevel::Amp8DBv5);
+        assert_eq!(BstVreg::Boost6V0.to_volts(), 6.0);
+        assert_eq!(BstVreg::Boost12V5.to_volts(), 12.5);
+    }
 }
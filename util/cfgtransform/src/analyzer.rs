@@ -38,7 +38,8 @@ pub fn analyze<'a>(
             page = value;
             None
         }
-        BOOK_REGISTER => {
+        // The book register only exists on page 0, other pages use it as regular memory.
+        BOOK_REGISTER if page == 0x00 => {
             book = value;
             None
         }
@@ -82,11 +83,19 @@ where
                 let page = ra.page;
 
                 if self.prev_book != Some(book) {
-                    self.prev_book = Some(book);
-                    self.prev_page = None; // When the book is reset, forget our assumptions about the page.
-                    crate::ast::WriteCommand {
-                        register: BOOK_REGISTER,
-                        bytes: vec![book],
+                    if self.prev_page != Some(0x00) {
+                        // The book can only be selected from page 0.
+                        self.prev_page = Some(0x00);
+                        crate::ast::WriteCommand {
+                            register: PAGE_REGISTER,
+                            bytes: vec![0x00],
+                        }
+                    } else {
+                        self.prev_book = Some(book);
+                        crate::ast::WriteCommand {
+                            register: BOOK_REGISTER,
+                            bytes: vec![book],
+                        }
                     }
                 } else if self.prev_page != Some(page) {
                     self.prev_page = Some(page);
@@ -103,7 +112,9 @@ where
                         let _ = self.it.next().unwrap();
 
                         // If max burst length is reached, or we are about to overwrite the book register.
-                        if bytes.len() > BURST_MAX_LEN || prev_register == BOOK_REGISTER - 1 {
+                        if bytes.len() > BURST_MAX_LEN
+                            || (page == 0x00 && prev_register == BOOK_REGISTER - 1)
+                        {
                             break;
                         }
 
@@ -161,6 +172,8 @@ mod test {
                 (0x01, 0x01, 0x05, 0xff),
                 (0x01, 0x01, 0x04, 0xfe),
                 (0x01, 0x01, 0x07, 0xff),
+                (0x01, 0x01, 0x7e, 0xff),
+                (0x01, 0x01, 0x7f, 0xff),
                 (0x00, 0x00, 0x07, 0xff),
             ]
             .into_iter()
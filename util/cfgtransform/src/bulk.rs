@@ -3,11 +3,12 @@ use itertools::Either;
 use crate::ast::{Command, WriteCommand};
 
 const CFG_META_BURST: u8 = 253;
+const CFG_META_DELAY: u8 = 254;
 
 pub struct BulkGenerator;
 
 impl BulkGenerator {
-    /// Generate bulk register write files, with "burst" transfers.
+    /// Generate bulk register write files, with "burst" transfers and delays.
     pub fn generate<'a>(
         commands: impl Iterator<Item = &'a Command> + 'a,
     ) -> impl Iterator<Item = u8> + 'a {
@@ -33,8 +34,8 @@ impl BulkGenerator {
                         ))
                     }
                 }
-                crate::ast::Command::Delay(_) => {
-                    Either::Right(Either::Right(std::iter::empty::<u8>()))
+                crate::ast::Command::Delay(duration) => {
+                    Either::Right(Either::Right([CFG_META_DELAY, *duration].into_iter()))
                 }
             })
             .flatten()
@@ -45,7 +46,7 @@ impl BulkGenerator {
 mod test {
     use crate::{
         ast::{Command, WriteCommand},
-        bulk::CFG_META_BURST,
+        bulk::{CFG_META_BURST, CFG_META_DELAY},
     };
 
     #[test]
@@ -87,6 +88,16 @@ mod test {
         assert_eq!(bulk.len() % 2, 0); // Assert that the byte buffer is composed of 16 bit words
     }
 
+    #[test]
+    fn delay() {
+        let command = Command::Delay(0x0a);
+        let bulk =
+            crate::bulk::BulkGenerator::generate(std::iter::once(&command)).collect::<Vec<_>>();
+
+        assert_eq!(bulk, &[CFG_META_DELAY, 0x0a]);
+        assert_eq!(bulk.len() % 2, 0); // Assert that the byte buffer is composed of 16 bit words
+    }
+
     #[test]
     fn mix() {
         let commands = vec![
@@ -43,6 +43,14 @@ fn main() {
     log::info!("Read {} commands", cmds.0.len());
 
     let cmds = if args.dedup {
+        let delays = cmds
+            .iter()
+            .filter(|cmd| matches!(cmd, ast::Command::Delay(_)))
+            .count();
+        if delays > 0 {
+            log::warn!("Deduplication drops {} delay commands", delays);
+        }
+
         let mut btree = analyzer::dedup(analyzer::analyze(cmds.into_iter()));
         log::info!("Got {} registers", btree.len());
 
@@ -106,6 +114,7 @@ mod test {
             > 00
             > 00
             w 98 08 3f
+            d 0a
         ";
 
         let cmds = crate::grammar::CommandsParser::new().parse(src).unwrap();
@@ -113,7 +122,7 @@ mod test {
 
         assert_eq!(
             bulk,
-            &[0, 0, 127, 0, 0, 5, 253, 4, 84, 0, 0, 0, 0, 0, 8, 63]
+            &[0, 0, 127, 0, 0, 5, 253, 4, 84, 0, 0, 0, 0, 0, 8, 63, 254, 10]
         );
     }
 }